@@ -4,234 +4,24 @@ extern crate test;
 
 use test::Bencher;
 
-use schaakmaat::chess::{Chess, Color, Outcome, Piece};
+use schaakmaat::chess::{Chess, Color, Outcome};
 use schaakmaat::computer;
-use schaakmaat::pos::Pos;
+use schaakmaat::transposition::TranspositionTable;
 
-const MATE_IN_THREE: Chess = Chess {
-    board: [
-        [
-            Some(Piece::BLACK_ROOK),
-            None,
-            Some(Piece::BLACK_BISHOP),
-            Some(Piece::BLACK_QUEEN),
-            None,
-            Some(Piece::BLACK_KNIGHT),
-            None,
-            Some(Piece::BLACK_ROOK),
-        ],
-        [
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::BLACK_PAWN),
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_QUEEN),
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::WHITE_BISHOP),
-        ],
-        [
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::BLACK_BISHOP),
-            None,
-            None,
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_ROOK),
-            None,
-            None,
-        ],
-        [
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::BLACK_KING),
-            None,
-            None,
-            None,
-        ],
-        [None, None, None, None, None, None, None, None],
-        [
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-        ],
-        [
-            Some(Piece::WHITE_ROOK),
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_KING),
-            None,
-        ],
-    ],
-    turn: Color::White,
-    kings: [Pos::new(6, 7), Pos::new(4, 5)],
-};
+const MATE_IN_THREE: &str = "r1bq1n1r/pp3QpB/2p1pb2/5R2/2pPk3/8/PPP3PP/R5K1 w - - 0 1";
 
-const MATE_IN_TWO: Chess = Chess {
-    board: [
-        [
-            None,
-            None,
-            None,
-            Some(Piece::BLACK_ROOK),
-            None,
-            None,
-            Some(Piece::BLACK_ROOK),
-            Some(Piece::BLACK_KING),
-        ],
-        [
-            Some(Piece::BLACK_PAWN),
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-            Some(Piece::BLACK_PAWN),
-        ],
-        [
-            None,
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::BLACK_PAWN),
-            None,
-        ],
-        [
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_ROOK),
-            None,
-            None,
-            None,
-            None,
-        ],
-        [
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-            Some(Piece::WHITE_BISHOP),
-            None,
-            None,
-            Some(Piece::WHITE_QUEEN),
-        ],
-        [None, None, None, None, None, None, None, None],
-        [
-            None,
-            Some(Piece::BLACK_QUEEN),
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_ROOK),
-            None,
-            Some(Piece::WHITE_KING),
-            None,
-        ],
-    ],
-    turn: Color::White,
-    kings: [Pos::new(6, 7), Pos::new(7, 0)],
-};
+const MATE_IN_TWO: &str = "3r2rk/p4p1p/3p1Pp1/3R4/2p1B2Q/8/1q4PP/4R1K1 w - - 0 1";
 
-const MATE_IN_ONE: Chess = Chess {
-    board: [
-        [
-            Some(Piece::BLACK_ROOK),
-            None,
-            Some(Piece::BLACK_BISHOP),
-            None,
-            None,
-            Some(Piece::BLACK_ROOK),
-            Some(Piece::BLACK_KING),
-            None,
-        ],
-        [
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::BLACK_PAWN),
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-        ],
-        [None, None, None, None, None, None, None, None],
-        [
-            None,
-            None,
-            None,
-            Some(Piece::BLACK_QUEEN),
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_KNIGHT),
-            None,
-            Some(Piece::WHITE_QUEEN),
-        ],
-        [None, None, None, None, None, None, None, None],
-        [None, None, None, None, None, None, None, None],
-        [
-            Some(Piece::WHITE_PAWN),
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-        ],
-        [
-            Some(Piece::BLACK_BISHOP),
-            None,
-            Some(Piece::WHITE_BISHOP),
-            None,
-            None,
-            Some(Piece::WHITE_ROOK),
-            None,
-            Some(Piece::WHITE_KING),
-        ],
-    ],
-    turn: Color::White,
-    kings: [Pos::new(7, 7), Pos::new(6, 0)],
-};
+const MATE_IN_ONE: &str = "r1b2rk1/pppp2p1/8/3qPN1Q/8/8/P5PP/b1B2R1K w - - 0 1";
 
 #[bench]
 #[ignore]
 fn mate_in_three(bencher: &mut Bencher) {
     bencher.iter(|| {
-        let mut state = MATE_IN_THREE;
+        let mut state = Chess::from_fen(MATE_IN_THREE).unwrap();
 
         for _ in 0..5 {
-            let best_move = computer::minimax(&state, 5).m.unwrap();
+            let best_move = computer::alpha_beta(&state, 5).m.unwrap();
             state.perform(best_move);
         }
 
@@ -242,7 +32,7 @@ fn mate_in_three(bencher: &mut Bencher) {
 #[bench]
 fn mate_in_two(bencher: &mut Bencher) {
     bencher.iter(|| {
-        let mut state = MATE_IN_TWO;
+        let mut state = Chess::from_fen(MATE_IN_TWO).unwrap();
 
         for _ in 0..3 {
             let best_move = computer::minimax(&state, 3).m.unwrap();
@@ -256,7 +46,7 @@ fn mate_in_two(bencher: &mut Bencher) {
 #[bench]
 fn mate_in_one(bencher: &mut Bencher) {
     bencher.iter(|| {
-        let mut state = MATE_IN_ONE;
+        let mut state = Chess::from_fen(MATE_IN_ONE).unwrap();
 
         for _ in 0..1 {
             let best_move = computer::minimax(&state, 1).m.unwrap();
@@ -266,3 +56,49 @@ fn mate_in_one(bencher: &mut Bencher) {
         assert_eq!(state.outcome(), Some(Outcome::Winner(Color::White)));
     });
 }
+
+#[bench]
+#[ignore]
+fn mate_in_three_with_table(bencher: &mut Bencher) {
+    bencher.iter(|| {
+        let mut state = Chess::from_fen(MATE_IN_THREE).unwrap();
+        let mut table = TranspositionTable::new(16);
+
+        for _ in 0..5 {
+            let best_move = computer::alpha_beta_with_table(&state, 5, &mut table)
+                .m
+                .unwrap();
+            state.perform(best_move);
+        }
+
+        assert_eq!(state.outcome(), Some(Outcome::Winner(Color::White)));
+    });
+}
+
+#[bench]
+fn mate_in_two_alpha_beta(bencher: &mut Bencher) {
+    bencher.iter(|| {
+        let mut state = Chess::from_fen(MATE_IN_TWO).unwrap();
+
+        for _ in 0..3 {
+            let best_move = computer::alpha_beta(&state, 3).m.unwrap();
+            state.perform(best_move);
+        }
+
+        assert_eq!(state.outcome(), Some(Outcome::Winner(Color::White)));
+    });
+}
+
+#[bench]
+fn mate_in_one_alpha_beta(bencher: &mut Bencher) {
+    bencher.iter(|| {
+        let mut state = Chess::from_fen(MATE_IN_ONE).unwrap();
+
+        for _ in 0..1 {
+            let best_move = computer::alpha_beta(&state, 1).m.unwrap();
+            state.perform(best_move);
+        }
+
+        assert_eq!(state.outcome(), Some(Outcome::Winner(Color::White)));
+    });
+}
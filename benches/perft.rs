@@ -0,0 +1,21 @@
+#![feature(test)]
+
+extern crate test;
+
+use test::Bencher;
+
+use schaakmaat::chess::Chess;
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+#[bench]
+fn starting_position(bencher: &mut Bencher) {
+    let state = Chess::new();
+    bencher.iter(|| assert_eq!(state.perft(3), 8_902));
+}
+
+#[bench]
+fn kiwipete(bencher: &mut Bencher) {
+    let state = Chess::from_fen(KIWIPETE).unwrap();
+    bencher.iter(|| assert_eq!(state.perft(2), 2_039));
+}
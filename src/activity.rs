@@ -0,0 +1,144 @@
+//! Judging how active the pieces are: how many tiles they reach, and whether they stand where
+//! they do the most. Every term counts for white and against black, so each can be tuned or
+//! looked at on its own.
+
+use crate::bitboard::{self, adjacent_files, file, row, Bitboard};
+use crate::chess::{Chess, Color, Kind, Piece};
+use crate::evaluation::Tapered;
+use crate::pawns::{ahead, relative_row};
+
+/// Returns all piece activity terms together.
+pub fn activity(chess: &Chess) -> Tapered {
+    mobility(chess)
+        + rooks_on_open_files(chess)
+        + bishop_pair(chess)
+        + knight_outposts(chess)
+        + rooks_on_seventh(chess)
+}
+
+/// Rewards pieces for the tiles they can move to, leaving out those attacked by enemy pawns,
+/// since a piece rarely wants to go there.
+pub fn mobility(chess: &Chess) -> Tapered {
+    for_both(|color| {
+        let occupied = chess.occupied();
+        let enemy_pawns = chess.bitboard(Piece::new(!color, Kind::Pawn));
+        let attacked = enemy_pawns
+            .into_iter()
+            .fold(Bitboard::EMPTY, |attacked, pos| {
+                attacked | bitboard::pawn_attacks(!color, pos)
+            });
+        let available = !chess.occupied_by(color) & !attacked;
+
+        MOBILITY
+            .iter()
+            .map(|&(kind, bonus, typical)| {
+                chess
+                    .bitboard(Piece::new(color, kind))
+                    .into_iter()
+                    .map(|from| {
+                        let tiles = bitboard::attacks(kind, color, from, occupied) & available;
+                        bonus * (tiles.len() as i16 - typical)
+                    })
+                    .sum::<Tapered>()
+            })
+            .sum()
+    })
+}
+
+/// Rewards rooks on columns without pawns of their own player, along which they can attack.
+pub fn rooks_on_open_files(chess: &Chess) -> Tapered {
+    for_both(|color| {
+        let own_pawns = chess.bitboard(Piece::new(color, Kind::Pawn));
+        let enemy_pawns = chess.bitboard(Piece::new(!color, Kind::Pawn));
+        chess
+            .bitboard(Piece::new(color, Kind::Rook))
+            .into_iter()
+            .map(|pos| {
+                let file = file(pos.x());
+                match (
+                    (own_pawns & file).is_empty(),
+                    (enemy_pawns & file).is_empty(),
+                ) {
+                    (true, true) => ROOK_OPEN_FILE,
+                    (true, false) => ROOK_HALF_OPEN_FILE,
+                    _ => Tapered::default(),
+                }
+            })
+            .sum()
+    })
+}
+
+/// Rewards keeping both bishops, which together reach tiles of either color.
+pub fn bishop_pair(chess: &Chess) -> Tapered {
+    for_both(|color| {
+        let bishops = chess.bitboard(Piece::new(color, Kind::Bishop));
+        if !(bishops & Bitboard::LIGHT).is_empty() && !(bishops & !Bitboard::LIGHT).is_empty() {
+            BISHOP_PAIR
+        } else {
+            Tapered::default()
+        }
+    })
+}
+
+/// Rewards knights on the fourth to sixth row from their player's side, protected by a pawn, that
+/// no enemy pawn can ever chase away.
+pub fn knight_outposts(chess: &Chess) -> Tapered {
+    for_both(|color| {
+        let own_pawns = chess.bitboard(Piece::new(color, Kind::Pawn));
+        let enemy_pawns = chess.bitboard(Piece::new(!color, Kind::Pawn));
+        chess
+            .bitboard(Piece::new(color, Kind::Knight))
+            .into_iter()
+            .filter(|&pos| {
+                // Its protecting pawns stand where an enemy pawn on its tile would attack
+                (3..=5).contains(&relative_row(color, pos))
+                    && !(bitboard::pawn_attacks(!color, pos) & own_pawns).is_empty()
+                    && (enemy_pawns & adjacent_files(pos.x()) & ahead(color, pos.y())).is_empty()
+            })
+            .map(|_| KNIGHT_OUTPOST)
+            .sum()
+    })
+}
+
+/// Rewards rooks on the second row from the opponent's side, where they attack the pawns that
+/// have not moved yet and confine the enemy king to the last row.
+pub fn rooks_on_seventh(chess: &Chess) -> Tapered {
+    for_both(|color| {
+        let seventh = match color {
+            Color::Black => 6,
+            Color::White => 1,
+        };
+        let eighth = match color {
+            Color::Black => 7,
+            Color::White => 0,
+        };
+        let enemy_pawns = chess.bitboard(Piece::new(!color, Kind::Pawn));
+        let enemy_king = chess.kings[(!color).king_index()];
+        if (enemy_pawns & row(seventh)).is_empty() && enemy_king.y() != eighth {
+            return Tapered::default();
+        }
+
+        let rooks = chess.bitboard(Piece::new(color, Kind::Rook)) & row(seventh);
+        ROOK_ON_SEVENTH * rooks.len() as i16
+    })
+}
+
+/// Adds a term for white and subtracts it for black.
+fn for_both(term: impl Fn(Color) -> Tapered) -> Tapered {
+    term(Color::White) - term(Color::Black)
+}
+
+/// The bonus per tile a piece can move to, for every kind of piece with a choice of tiles, and
+/// the number of tiles it typically has. Fewer tiles than that cost as much as more gain.
+const MOBILITY: [(Kind, Tapered, i16); 4] = [
+    (Kind::Knight, Tapered::new(4, 4), 4),
+    (Kind::Bishop, Tapered::new(5, 5), 6),
+    (Kind::Rook, Tapered::new(2, 4), 6),
+    (Kind::Queen, Tapered::new(1, 2), 12),
+];
+
+const ROOK_OPEN_FILE: Tapered = Tapered::new(25, 10);
+const ROOK_HALF_OPEN_FILE: Tapered = Tapered::new(12, 6);
+const BISHOP_PAIR: Tapered = Tapered::new(30, 50);
+const KNIGHT_OUTPOST: Tapered = Tapered::new(20, 10);
+const ROOK_ON_SEVENTH: Tapered = Tapered::new(20, 30);
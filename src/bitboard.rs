@@ -0,0 +1,365 @@
+//! Sets of tiles stored as the bits of a `u64`, one bit per tile, and tables of the tiles every
+//! kind of piece attacks from every tile. Combining sets takes a single instruction, so e.g. all
+//! tiles a knight can move to are its attacks without the tiles occupied by its own pieces.
+
+use std::fmt::{Debug, Formatter};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::chess::{Color, Kind};
+use crate::pos::Pos;
+
+/// A set of tiles. The tile at `(x, y)` is bit `y * 8 + x`, so `a8` is the lowest bit and `h1`
+/// the highest.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+    /// The light tiles, like `a8` and `h1`.
+    pub const LIGHT: Bitboard = Bitboard(0xAA55_AA55_AA55_AA55);
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.0 & Bitboard::from(pos).0 != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the number of tiles in the set.
+    pub fn len(&self) -> u32 {
+        self.0.count_ones()
+    }
+}
+
+impl From<Pos> for Bitboard {
+    fn from(pos: Pos) -> Self {
+        Bitboard(1 << square(pos))
+    }
+}
+
+impl Debug for Bitboard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(*self).finish()
+    }
+}
+
+impl IntoIterator for Bitboard {
+    type Item = Pos;
+    type IntoIter = Tiles;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Tiles(self.0)
+    }
+}
+
+/// Iterates over the tiles of a [`Bitboard`], from `a8` to `h1`.
+#[derive(Copy, Clone, Debug)]
+pub struct Tiles(u64);
+
+impl Iterator for Tiles {
+    type Item = Pos;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.0 != 0).then(|| {
+            let square = self.0.trailing_zeros() as usize;
+            // Clears the lowest set bit
+            self.0 &= self.0 - 1;
+            pos(square)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Tiles {}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Bitboard;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 ^ rhs.0)
+    }
+}
+
+impl BitXorAssign for Bitboard {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Self::Output {
+        Bitboard(!self.0)
+    }
+}
+
+/// Returns the tiles a piece of the given kind and color on `from` attacks, i.e. could capture on,
+/// with the given tiles occupied. For a pawn, these are only its diagonal captures.
+pub fn attacks(kind: Kind, color: Color, from: Pos, occupied: Bitboard) -> Bitboard {
+    match kind {
+        Kind::Pawn => pawn_attacks(color, from),
+        Kind::Rook => rook_attacks(from, occupied),
+        Kind::Knight => knight_attacks(from),
+        Kind::Bishop => bishop_attacks(from, occupied),
+        Kind::Queen => rook_attacks(from, occupied) | bishop_attacks(from, occupied),
+        Kind::King => king_attacks(from),
+    }
+}
+
+pub fn pawn_attacks(color: Color, from: Pos) -> Bitboard {
+    Bitboard(PAWN_ATTACKS[color.king_index()][square(from)])
+}
+
+pub fn knight_attacks(from: Pos) -> Bitboard {
+    Bitboard(KNIGHT_ATTACKS[square(from)])
+}
+
+pub fn king_attacks(from: Pos) -> Bitboard {
+    Bitboard(KING_ATTACKS[square(from)])
+}
+
+pub fn rook_attacks(from: Pos, occupied: Bitboard) -> Bitboard {
+    [UP, RIGHT, DOWN, LEFT]
+        .into_iter()
+        .fold(Bitboard::EMPTY, |attacks, dir| {
+            attacks | ray_attacks(dir, from, occupied)
+        })
+}
+
+pub fn bishop_attacks(from: Pos, occupied: Bitboard) -> Bitboard {
+    [UP_RIGHT, DOWN_RIGHT, DOWN_LEFT, UP_LEFT]
+        .into_iter()
+        .fold(Bitboard::EMPTY, |attacks, dir| {
+            attacks | ray_attacks(dir, from, occupied)
+        })
+}
+
+/// Returns the tiles strictly between two tiles on the same row, column or diagonal, or no tiles if
+/// they are not in line.
+pub fn between(a: Pos, b: Pos) -> Bitboard {
+    Bitboard(BETWEEN[square(a)][square(b)])
+}
+
+/// Returns the whole row, column or diagonal through two tiles, from edge to edge, or no tiles if
+/// they are not in line.
+pub fn line(a: Pos, b: Pos) -> Bitboard {
+    Bitboard(LINE[square(a)][square(b)])
+}
+
+/// Returns the column with the given `x`.
+pub fn file(x: isize) -> Bitboard {
+    Bitboard(0x0101_0101_0101_0101 << x)
+}
+
+/// Returns the columns beside the column with the given `x`.
+pub fn adjacent_files(x: isize) -> Bitboard {
+    let left = if x > 0 { file(x - 1) } else { Bitboard::EMPTY };
+    let right = if x < 7 { file(x + 1) } else { Bitboard::EMPTY };
+    left | right
+}
+
+/// Returns the row with the given `y`, or no tiles if it is off the board.
+pub fn row(y: isize) -> Bitboard {
+    match y {
+        0..=7 => Bitboard(0xff << (y * 8)),
+        _ => Bitboard::EMPTY,
+    }
+}
+
+/// Returns the tiles in the given direction from `from`, up to and including the first occupied
+/// one. The ray beyond that tile is the ray from that tile onwards, so it can simply be removed.
+fn ray_attacks(dir: usize, from: Pos, occupied: Bitboard) -> Bitboard {
+    let ray = RAYS[dir][square(from)];
+    let blockers = ray & occupied.0;
+    if blockers == 0 {
+        return Bitboard(ray);
+    }
+
+    // Rays going right or down go towards higher bits, so the nearest blocker is the lowest bit
+    let blocker = if DIRS[dir].0 + DIRS[dir].1 * 8 > 0 {
+        blockers.trailing_zeros()
+    } else {
+        63 - blockers.leading_zeros()
+    };
+    Bitboard(ray ^ RAYS[dir][blocker as usize])
+}
+
+fn square(pos: Pos) -> usize {
+    (pos.y() * 8 + pos.x()) as usize
+}
+
+fn pos(square: usize) -> Pos {
+    Pos::new((square % 8) as isize, (square / 8) as isize)
+}
+
+/// The directions sliders move in as `(dx, dy)`, in the same order as [`Shift::DIRS`].
+///
+/// [`Shift::DIRS`]: crate::pos::Shift::DIRS
+const DIRS: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+const UP: usize = 0;
+const UP_RIGHT: usize = 1;
+const RIGHT: usize = 2;
+const DOWN_RIGHT: usize = 3;
+const DOWN: usize = 4;
+const DOWN_LEFT: usize = 5;
+const LEFT: usize = 6;
+const UP_LEFT: usize = 7;
+
+const JUMPS: [(isize, isize); 8] = [
+    (1, -2),
+    (2, -1),
+    (2, 1),
+    (1, 2),
+    (-1, 2),
+    (-2, 1),
+    (-2, -1),
+    (-1, -2),
+];
+
+const KNIGHT_ATTACKS: [u64; 64] = leaper_attacks(&JUMPS);
+
+const KING_ATTACKS: [u64; 64] = leaper_attacks(&DIRS);
+
+/// Indexed by [`Color::king_index`]. White pawns attack upwards, black pawns downwards.
+const PAWN_ATTACKS: [[u64; 64]; 2] = [
+    leaper_attacks(&[(-1, -1), (1, -1)]),
+    leaper_attacks(&[(-1, 1), (1, 1)]),
+];
+
+/// The tiles in every direction from every tile up to the edge of the board, indexed by
+/// `[direction][square]`.
+const RAYS: [[u64; 64]; 8] = {
+    let mut rays = [[0; 64]; 8];
+    let mut dir = 0;
+    while dir < 8 {
+        let mut square = 0;
+        while square < 64 {
+            let (mut x, mut y) = ((square % 8) as isize, (square / 8) as isize);
+            loop {
+                x += DIRS[dir].0;
+                y += DIRS[dir].1;
+                if x < 0 || x >= 8 || y < 0 || y >= 8 {
+                    break;
+                }
+                rays[dir][square] |= 1 << (y * 8 + x);
+            }
+            square += 1;
+        }
+        dir += 1;
+    }
+    rays
+};
+
+/// The tiles strictly between every pair of tiles in line, indexed by `[square][square]`.
+static BETWEEN: [[u64; 64]; 64] = {
+    let mut between = [[0; 64]; 64];
+    let mut square = 0;
+    while square < 64 {
+        let mut dir = 0;
+        while dir < 8 {
+            // Walks along the ray, remembering the tiles passed so far
+            let (mut x, mut y) = ((square % 8) as isize, (square / 8) as isize);
+            let mut passed = 0;
+            loop {
+                x += DIRS[dir].0;
+                y += DIRS[dir].1;
+                if x < 0 || x >= 8 || y < 0 || y >= 8 {
+                    break;
+                }
+                between[square][(y * 8 + x) as usize] = passed;
+                passed |= 1 << (y * 8 + x);
+            }
+            dir += 1;
+        }
+        square += 1;
+    }
+    between
+};
+
+/// The row, column or diagonal through every pair of tiles in line, indexed by
+/// `[square][square]`.
+static LINE: [[u64; 64]; 64] = {
+    let mut line = [[0; 64]; 64];
+    let mut square = 0;
+    while square < 64 {
+        let mut dir = 0;
+        while dir < 8 {
+            // The opposite direction is four steps further along `DIRS`
+            let whole = RAYS[dir][square] | RAYS[(dir + 4) % 8][square] | 1 << square;
+            let mut rest = RAYS[dir][square];
+            while rest != 0 {
+                let next = rest.trailing_zeros() as usize;
+                line[square][next] = whole;
+                rest &= rest - 1;
+            }
+            dir += 1;
+        }
+        square += 1;
+    }
+    line
+};
+
+/// Builds the table of tiles a piece reaches from every tile by jumping by one of the given
+/// shifts.
+const fn leaper_attacks(shifts: &[(isize, isize)]) -> [u64; 64] {
+    let mut attacks = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let mut i = 0;
+        while i < shifts.len() {
+            let x = (square % 8) as isize + shifts[i].0;
+            let y = (square / 8) as isize + shifts[i].1;
+            if 0 <= x && x < 8 && 0 <= y && y < 8 {
+                attacks[square] |= 1 << (y * 8 + x);
+            }
+            i += 1;
+        }
+        square += 1;
+    }
+    attacks
+}
@@ -1,18 +1,53 @@
 use std::borrow::Borrow;
 use std::fmt::{Debug, Display, Formatter};
-use std::ops::{Index, IndexMut, Not};
+use std::hash::{Hash, Hasher};
+use std::iter::Take;
+use std::ops::{Deref, DerefMut, Index, Not};
+use std::{array, slice};
 
 use owo_colors::OwoColorize;
 
+use crate::bitboard::{self, Bitboard};
+use crate::computer::Score;
+use crate::evaluation::{self, Tapered};
 use crate::pos::{Pos, Shift};
+use crate::zobrist;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+/// A position on the board. It is set up with [`new`] or [`from_fen`] and changed only by moves,
+/// so that the bitboards and hashes derived from the pieces always agree with them.
+///
+/// [`new`]: #method.new
+/// [`from_fen`]: #method.from_fen
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Chess {
-    pub board: [[Option<Piece>; 8]; 8],
-    pub turn: Color,
+    pub(crate) board: [[Option<Piece>; 8]; 8],
+    pub(crate) turn: Color,
     /// Keeps track of the current positions of both kings. White's king's position is stored on
     /// index 0 and black's on 1.
-    pub kings: [Pos; 2],
+    pub(crate) kings: [Pos; 2],
+    /// Keeps track of which castling moves each player still has the right to perform. Indexed
+    /// the same way as `kings`.
+    pub(crate) castling: [Castling; 2],
+    /// The tile a pawn skipped over by leaping two tiles forward on the previous move, which can
+    /// be captured onto en passant. `None` if the previous move was not a leap.
+    pub(crate) en_passant: Option<Pos>,
+    /// The number of moves since the last capture or pawn move, used for the fifty-move rule.
+    pub(crate) halfmove_clock: u16,
+    /// The number of the current full move, starting at 1 and incremented after black moves.
+    pub(crate) fullmove_number: u16,
+    /// The tiles occupied by each kind of piece of either color, indexed by kind, kept up to date
+    /// by [`perform`] alongside `board`.
+    ///
+    /// [`perform`]: #method.perform
+    pub(crate) by_kind: [Bitboard; 6],
+    /// The tiles occupied by each player's pieces, indexed the same way as `kings`.
+    pub(crate) by_color: [Bitboard; 2],
+    /// The Zobrist hash of the position, kept up to date by [`perform`].
+    ///
+    /// [`perform`]: #method.perform
+    pub(crate) zobrist: u64,
+    /// The Zobrist hash of only the pawns, kept up to date like `zobrist`.
+    pub(crate) pawn_zobrist: u64,
 }
 
 impl Chess {
@@ -68,7 +103,153 @@ impl Chess {
 
         let kings = [Pos::new(4, 7), Pos::new(4, 0)];
 
-        Chess { board, turn, kings }
+        let castling = [Castling::BOTH; 2];
+
+        let en_passant = None;
+
+        let mut chess = Chess {
+            board,
+            turn,
+            kings,
+            castling,
+            en_passant,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            by_kind: [Bitboard::EMPTY; 6],
+            by_color: [Bitboard::EMPTY; 2],
+            zobrist: 0,
+            pawn_zobrist: 0,
+        };
+        chess.fill_bitboards();
+        chess.zobrist = chess.compute_zobrist();
+        chess.pawn_zobrist = chess.compute_pawn_zobrist();
+        chess
+    }
+
+    /// Returns the pieces on the board, indexed by row and then column, with the eighth row first.
+    pub fn board(&self) -> &[[Option<Piece>; 8]; 8] {
+        &self.board
+    }
+
+    /// Returns the player to move.
+    pub fn turn(&self) -> Color {
+        self.turn
+    }
+
+    /// Returns the positions of both kings, white's on index 0 and black's on 1.
+    pub fn kings(&self) -> [Pos; 2] {
+        self.kings
+    }
+
+    /// Returns the castling moves each player still has the right to perform, indexed the same
+    /// way as [`kings`].
+    ///
+    /// [`kings`]: #method.kings
+    pub fn castling(&self) -> [Castling; 2] {
+        self.castling
+    }
+
+    /// Returns the tile that can be captured onto en passant, if the previous move was a leap.
+    pub fn en_passant(&self) -> Option<Pos> {
+        self.en_passant
+    }
+
+    /// Returns the number of moves since the last capture or pawn move.
+    pub fn halfmove_clock(&self) -> u16 {
+        self.halfmove_clock
+    }
+
+    /// Returns the number of the current full move, starting at 1.
+    pub fn fullmove_number(&self) -> u16 {
+        self.fullmove_number
+    }
+
+    /// Sets up the bitboards from scratch from the pieces on `board`.
+    pub(crate) fn fill_bitboards(&mut self) {
+        for (y, row) in self.board.iter().enumerate() {
+            for (x, piece) in row.iter().enumerate() {
+                if let Some(piece) = piece {
+                    let tile = Bitboard::from(Pos::new(x as isize, y as isize));
+                    self.by_kind[piece.kind as usize] |= tile;
+                    self.by_color[piece.color.king_index()] |= tile;
+                }
+            }
+        }
+    }
+
+    /// Returns the tiles occupied by the given piece.
+    pub fn bitboard(&self, piece: Piece) -> Bitboard {
+        self.by_kind[piece.kind as usize] & self.by_color[piece.color.king_index()]
+    }
+
+    /// Returns the tiles occupied by the given player's pieces.
+    pub fn occupied_by(&self, player: Color) -> Bitboard {
+        self.by_color[player.king_index()]
+    }
+
+    /// Returns the tiles occupied by any piece.
+    pub fn occupied(&self) -> Bitboard {
+        self.by_color[0] | self.by_color[1]
+    }
+
+    /// Returns a 64-bit identity of the position: the pieces, the player to move, the castling
+    /// rights, and the column that can be captured onto en passant. Unlike the derived hashes of
+    /// other types, it is updated incrementally with every move. Equal positions reached through
+    /// different moves have the same hash, even if their move counters differ.
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
+    /// Returns a 64-bit identity of only the pawns in the position, like [`zobrist`]. Positions
+    /// with the same pawns have the same pawn structure, whatever the other pieces do.
+    ///
+    /// [`zobrist`]: #method.zobrist
+    pub fn pawn_zobrist(&self) -> u64 {
+        self.pawn_zobrist
+    }
+
+    /// Computes the Zobrist hash of the position from scratch.
+    pub(crate) fn compute_zobrist(&self) -> u64 {
+        let mut zobrist = self.pieces().fold(0, |zobrist, (pos, piece)| {
+            zobrist ^ zobrist::piece(piece, pos)
+        });
+        if self.turn == Color::Black {
+            zobrist ^= zobrist::TURN;
+        }
+        zobrist ^ zobrist::castling(self.castling) ^ self.en_passant_zobrist()
+    }
+
+    /// Computes the Zobrist hash of only the pawns from scratch.
+    pub(crate) fn compute_pawn_zobrist(&self) -> u64 {
+        self.pieces()
+            .filter(|(_, piece)| piece.kind == Kind::Pawn)
+            .fold(0, |zobrist, (pos, piece)| {
+                zobrist ^ zobrist::piece(piece, pos)
+            })
+    }
+
+    /// Returns the part of the Zobrist hash that depends on the en passant tile. It is only
+    /// included when the current player has a pawn that could capture onto it, so that a leap
+    /// nobody can respond to does not make an otherwise equal position look different.
+    fn en_passant_zobrist(&self) -> u64 {
+        // The leaping pawn is one tile beyond the en passant tile, and can be captured by pawns
+        // beside it
+        let beyond = match self.turn {
+            Color::Black => Shift::UP,
+            Color::White => Shift::DOWN,
+        };
+        let pawn = Some(Piece::new(self.turn, Kind::Pawn));
+
+        match self.en_passant {
+            Some(pos)
+                if [Shift::LEFT, Shift::RIGHT]
+                    .iter()
+                    .any(|dir| self[pos + beyond + *dir] == pawn) =>
+            {
+                zobrist::en_passant(pos)
+            }
+            _ => 0,
+        }
     }
 
     /// Checks whether a given position is on the board.
@@ -76,173 +257,473 @@ impl Chess {
         (0 <= pos.x() && pos.x() < 8) && (0 <= pos.y() && pos.y() < 8)
     }
 
-    /// Returns an iterator over all positions of a chess board.
-    fn board_positions() -> impl Iterator<Item = Pos> {
-        (0..8).flat_map(|x| (0..8).map(move |y| Pos::new(x, y)))
-    }
-
     /// Returns an iterator over all pieces on the board.
-    fn pieces(&self) -> impl Iterator<Item = (Pos, Piece)> + '_ {
-        Self::board_positions().filter_map(|pos| self[pos].map(|piece| (pos, piece)))
+    pub(crate) fn pieces(&self) -> impl Iterator<Item = (Pos, Piece)> + '_ {
+        self.occupied()
+            .into_iter()
+            .filter_map(|pos| self[pos].map(|piece| (pos, piece)))
     }
 
     /// Generates all legal moves for the current player.
-    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
-        self.unsafe_moves(self.turn).filter(|m| self.is_safe(*m))
+    pub fn moves(&self) -> impl Iterator<Item = Move> {
+        let mut moves = MoveList::new();
+        self.generate_into(&mut moves);
+        moves.into_iter()
     }
 
-    /// Checks whether performing a move does not check the current player's own king.
-    fn is_safe(&self, m: Move) -> bool {
-        let mut copy = *self;
-        copy.perform(m);
-        !copy.is_checked(self.turn)
+    /// Generates the legal moves for the current player that capture a piece or promote a pawn,
+    /// i.e. the moves that change the material on the board.
+    pub fn captures(&self) -> impl Iterator<Item = Move> {
+        let mut moves = MoveList::new();
+        self.generate_captures_into(&mut moves);
+        moves.into_iter()
     }
 
-    /// Generates all moves for the given player, even those that would check their own king.
-    fn unsafe_moves(&self, player: Color) -> impl Iterator<Item = Move> + '_ {
-        self.pieces()
-            .filter(move |(_, piece)| piece.color == player)
-            .flat_map(move |(from, piece)| match piece.kind {
-                Kind::Pawn => {
-                    let (step, captures, start_row) = match player {
-                        Color::Black => (Shift::DOWN, vec![Shift::DOWN_RIGHT, Shift::DOWN_LEFT], 1),
-                        Color::White => (Shift::UP, vec![Shift::UP_LEFT, Shift::UP_RIGHT], 6),
-                    };
-
-                    let captures = captures
-                        .into_iter()
-                        .map(move |dir| from + dir)
-                        .filter(move |to| {
-                            self[to]
-                                .as_ref()
-                                .map(|piece| piece.color != player)
-                                .unwrap_or_default()
-                        })
-                        .map(move |to| Move::new(from, to));
-
-                    let to = from + step;
-                    let too = from + step * 2;
-
-                    let step = (self[to].is_none() && Self::on_board(&to)).then(|| Move::new(from, to));
-                    let leap = (from.y() == start_row && self[to].is_none() && self[too].is_none())
-                        .then(|| Move::new(from, too));
-
-                    Box::new(captures.chain(leap).chain(step)) as Box<dyn Iterator<Item = Move>>
+    /// Generates all legal moves for the current player into the given list, replacing what was
+    /// in it. Unlike [`moves`], this does not copy the moves out of the list, so a search can
+    /// reuse one list per ply.
+    ///
+    /// [`moves`]: #method.moves
+    pub fn generate_into(&self, moves: &mut MoveList) {
+        self.generate(moves, false);
+    }
+
+    /// Generates the moves of [`captures`] into the given list, replacing what was in it.
+    ///
+    /// [`captures`]: #method.captures
+    pub fn generate_captures_into(&self, moves: &mut MoveList) {
+        self.generate(moves, true);
+    }
+
+    /// Checks whether a move of the current player captures a piece, including en passant.
+    pub fn is_capture(&self, m: Move) -> bool {
+        self[m.to].is_some()
+            || (Some(m.to) == self.en_passant
+                && self[m.from].is_some_and(|piece| piece.kind == Kind::Pawn))
+    }
+
+    /// Generates the legal moves for the current player, or only those changing the material.
+    ///
+    /// Rather than trying each move and checking whether it leaves the king in check, moves that
+    /// would are never generated: the king only moves to tiles that are not attacked, a piece
+    /// between the king and an enemy slider only moves along the line it is pinned to, and while
+    /// in check, the other pieces must capture the checking piece or block its attack.
+    fn generate(&self, moves: &mut MoveList, captures_only: bool) {
+        moves.clear();
+
+        let player = self.turn;
+        let own = self.occupied_by(player);
+        let enemies = self.occupied_by(!player);
+        let occupied = self.occupied();
+        let king = self.kings[player.king_index()];
+        let targets = if captures_only { enemies } else { !own };
+
+        // The king is taken off the board while checking its targets, since it would otherwise
+        // hide the tiles behind it from a slider attacking it along the same line
+        let without_king = occupied ^ Bitboard::from(king);
+        for to in bitboard::king_attacks(king) & targets {
+            if self.attackers(to, !player, without_king).is_empty() {
+                moves.push(Move::new(king, to));
+            }
+        }
+
+        let checkers = self.attackers(king, !player, occupied);
+        if checkers.len() > 1 {
+            // Only the king can get out of a double check
+            return;
+        }
+        let check_mask = match checkers.into_iter().next() {
+            Some(checker) => Bitboard::from(checker) | bitboard::between(king, checker),
+            None => Bitboard::FULL,
+        };
+        let pinned = self.pinned(player);
+        let allowed = |from: Pos| {
+            if pinned.contains(from) {
+                check_mask & bitboard::line(king, from)
+            } else {
+                check_mask
+            }
+        };
+
+        let pawns = self.bitboard(Piece::new(player, Kind::Pawn));
+        for from in own & !pawns & !Bitboard::from(king) {
+            let kind = self[from].expect("bitboards out of sync").kind;
+            let attacks = bitboard::attacks(kind, player, from, occupied);
+            for to in attacks & targets & allowed(from) {
+                moves.push(Move::new(from, to));
+            }
+        }
+
+        for from in pawns {
+            self.generate_pawn_moves(moves, from, allowed(from), captures_only);
+        }
+
+        // The pawns that can capture onto the en passant tile are those that a pawn of the other
+        // player on that tile would attack
+        if let Some(to) = self.en_passant {
+            for from in bitboard::pawn_attacks(!player, to) & pawns {
+                if self.is_legal_en_passant(from, to, check_mask) {
+                    moves.push(Move::new(from, to));
                 }
-                Kind::Rook => Box::new(Shift::CARDINAL_DIRS.iter().flat_map(move |dir| {
-                    let mut capture = false;
-                    (1..)
-                        .map(move |distance| from + *dir * distance)
-                        .take_while(Self::on_board)
-                        .take_while(move |to| self.is_traversable(player, to, &mut capture))
-                        .map(move |to| Move::new(from, to))
-                })),
-                Kind::Knight => Box::new(
-                    Shift::JUMPS
-                        .iter()
-                        .map(move |jump| from + *jump)
-                        .filter(Self::on_board)
-                        .filter(move |to| {
-                            self[to]
-                                .as_ref()
-                                .map(|piece| piece.color != player)
-                                .unwrap_or(true)
-                        })
-                        .map(move |to| Move::new(from, to)),
-                ),
-                Kind::Bishop => Box::new(Shift::DIAGONAL_DIRS.iter().flat_map(move |dir| {
-                    let mut capture = false;
-                    (1..)
-                        .map(move |distance| from + *dir * distance)
-                        .take_while(Self::on_board)
-                        .take_while(move |to| self.is_traversable(player, to, &mut capture))
-                        .map(move |to| Move::new(from, to))
-                })),
-                Kind::Queen => Box::new(Shift::DIRS.iter().flat_map(move |dir| {
-                    let mut capture = false;
-                    (1..)
-                        .map(move |distance| from + *dir * distance)
-                        .take_while(Self::on_board)
-                        .take_while(move |to| self.is_traversable(player, to, &mut capture))
-                        .map(move |to| Move::new(from, to))
-                })),
-                Kind::King => Box::new(
-                    Shift::DIRS
-                        .iter()
-                        .map(move |dir| from + *dir)
-                        .filter(Self::on_board)
-                        .filter(move |to| {
-                            self[to]
-                                .as_ref()
-                                .map(|piece| piece.color != player)
-                                .unwrap_or(true)
-                        })
-                        .map(move |to| Move::new(from, to)),
-                ),
-            })
+            }
+        }
+
+        if checkers.is_empty() && !captures_only {
+            for m in self.castles() {
+                moves.push(m);
+            }
+        }
     }
 
-    /// Performs a move, changing the board state.
-    pub fn perform(&mut self, m: Move) {
-        if self[m.from].unwrap().kind == Kind::King {
-            self.kings[self.turn.king_index()] = m.to;
+    /// Generates the moves of the current player's pawn on `from` that end on one of the `allowed`
+    /// tiles: a step forward, a leap of two tiles from its starting row, and diagonal captures,
+    /// except en passant. With `captures_only`, the steps are only generated when they promote.
+    fn generate_pawn_moves(
+        &self,
+        moves: &mut MoveList,
+        from: Pos,
+        allowed: Bitboard,
+        captures_only: bool,
+    ) {
+        let player = self.turn;
+        let (step, start_row) = match player {
+            Color::Black => (Shift::DOWN, 1),
+            Color::White => (Shift::UP, 6),
+        };
+
+        // Pawns reaching the opponent's home row promote, which can be to any of these
+        let last_row = (!player).home_row();
+        let mut push = |to: Pos| {
+            if to.y() == last_row {
+                for promotion in Kind::PROMOTIONS {
+                    moves.push(Move {
+                        from,
+                        to,
+                        promotion,
+                    });
+                }
+            } else {
+                moves.push(Move::new(from, to));
+            }
+        };
+
+        for to in bitboard::pawn_attacks(player, from) & self.occupied_by(!player) & allowed {
+            push(to);
+        }
+
+        let to = from + step;
+        let too = to + step;
+        if self[to].is_none() {
+            if allowed.contains(to) && (!captures_only || to.y() == last_row) {
+                push(to);
+            }
+            if from.y() == start_row
+                && self[too].is_none()
+                && allowed.contains(too)
+                && !captures_only
+            {
+                push(too);
+            }
         }
-        self[m.to] = self[m.from].take();
-        self.turn = !self.turn;
     }
 
-    /// Evaluates how many "points" a board state is worth. A positive score indicates that white is
-    /// in a favorable position, and a negative score indicates that black is currently better off.
-    pub fn evaluate(&self) -> i16 {
-        match self.outcome() {
-            None => self.pieces().map(|(_, piece)| piece.base_value()).sum(),
-            Some(outcome) => outcome.value(),
+    /// Checks whether the current player's pawn on `from` may capture en passant onto `to`. The
+    /// captured pawn is not on `to`, so capturing it also gets out of a check it gives. And since
+    /// two pawns disappear from the same row at once, pins do not tell whether that uncovers an
+    /// attack on the king, so the enemy sliders are checked directly.
+    fn is_legal_en_passant(&self, from: Pos, to: Pos, check_mask: Bitboard) -> bool {
+        let captured = Pos::new(to.x(), from.y());
+        if !check_mask.contains(to) && !check_mask.contains(captured) {
+            return false;
         }
+
+        let king = self.kings[self.turn.king_index()];
+        let occupied =
+            self.occupied() ^ Bitboard::from(from) ^ Bitboard::from(captured) ^ Bitboard::from(to);
+        let enemy = |kind| self.bitboard(Piece::new(!self.turn, kind));
+        let queens = enemy(Kind::Queen);
+
+        (bitboard::rook_attacks(king, occupied) & (enemy(Kind::Rook) | queens)).is_empty()
+            && (bitboard::bishop_attacks(king, occupied) & (enemy(Kind::Bishop) | queens))
+                .is_empty()
     }
 
-    /// For pieces that can move entire rows, lanes, or diagonals, this function checks whether they
-    /// can continue moving in a straight line.
+    /// Returns the given player's pieces that are the only piece between their king and an enemy
+    /// slider, and may therefore only move along the line between them.
+    fn pinned(&self, player: Color) -> Bitboard {
+        let king = self.kings[player.king_index()];
+        let enemy = |kind| self.bitboard(Piece::new(!player, kind));
+        let queens = enemy(Kind::Queen);
+
+        // The sliders that would attack the king if nothing stood in between
+        let snipers = (bitboard::rook_attacks(king, Bitboard::EMPTY)
+            & (enemy(Kind::Rook) | queens))
+            | (bitboard::bishop_attacks(king, Bitboard::EMPTY) & (enemy(Kind::Bishop) | queens));
+
+        snipers
+            .into_iter()
+            .map(|sniper| bitboard::between(king, sniper) & self.occupied())
+            .filter(|between| between.len() == 1)
+            .fold(Bitboard::EMPTY, |pinned, between| pinned | between)
+            & self.occupied_by(player)
+    }
+
+    /// Generates the castling moves for the current player.
     ///
-    /// Pieces can move as long a tiles are empty, but must stop immediately upon encountering a
-    /// piece of the same color. They can continue if they encounter a piece of the opposite color
-    /// to capture it, but cannot continue after that. This is what the `capture` variable is used
-    /// for; it should be set to false at the start of the row, lane, or diagonal, and this function
-    /// will set it to true as soon as a piece of the opposite color is detected, stopping on the
-    /// next tile.
+    /// The king may not castle out of, through, or into check, and all tiles between the king and
+    /// the rook must be empty.
+    fn castles(&self) -> impl Iterator<Item = Move> + '_ {
+        let player = self.turn;
+        let rights = self.castling[player.king_index()];
+        let king = Pos::new(4, player.home_row());
+
+        [
+            (rights.king_side, 7, Shift::RIGHT),
+            (rights.queen_side, 0, Shift::LEFT),
+        ]
+        .into_iter()
+        .filter(move |(allowed, _, _)| *allowed)
+        .filter(move |(_, rook_x, dir)| {
+            let rook = Pos::new(*rook_x, king.y());
+            self[king] == Some(Piece::new(player, Kind::King))
+                && self[rook] == Some(Piece::new(player, Kind::Rook))
+                && (1..)
+                    .map(|distance| king + *dir * distance)
+                    .take_while(|pos| *pos != rook)
+                    .all(|pos| self[pos].is_none())
+                && (0..=2)
+                    .map(|distance| king + *dir * distance)
+                    .all(|pos| !self.is_attacked(pos, !player))
+        })
+        .map(move |(_, _, dir)| Move::new(king, king + dir * 2))
+    }
+
+    /// Performs a move like [`perform`], returning what is needed to take it back with
+    /// [`unmake_move`].
     ///
-    /// Note that this function does not check whether the destination is on the board or not.
-    /// [`on_board`] can be used first for that purpose.
+    /// [`perform`]: #method.perform
+    /// [`unmake_move`]: #method.unmake_move
+    pub fn make_move(&mut self, m: Move) -> Undo {
+        let piece = self[m.from].expect("no piece to move");
+        let captured = if piece.kind == Kind::Pawn && Some(m.to) == self.en_passant {
+            // Capturing en passant, the captured pawn is beside the moving pawn
+            let pos = Pos::new(m.to.x(), m.from.y());
+            self[pos].map(|captured| (pos, captured))
+        } else {
+            self[m.to].map(|captured| (m.to, captured))
+        };
+
+        let undo = Undo {
+            m,
+            piece,
+            captured,
+            castling: self.castling,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            zobrist: self.zobrist,
+        };
+        self.perform(m);
+        undo
+    }
+
+    /// Takes back the move made by [`make_move`], which must be the last move performed,
+    /// restoring the board state from before it.
     ///
-    /// [`on_board`]: #method.on_board
-    fn is_traversable(&self, player: Color, to: &Pos, capture: &mut bool) -> bool {
-        if *capture {
-            false
+    /// [`make_move`]: #method.make_move
+    pub fn unmake_move(&mut self, undo: Undo) {
+        let m = undo.m;
+        self.turn = !self.turn;
+
+        self.take(m.to);
+        self.place(m.from, undo.piece);
+
+        let dx = m.to.x() - m.from.x();
+        if undo.piece.kind == Kind::King {
+            self.kings[self.turn.king_index()] = m.from;
+
+            // Castling, so the rook jumps back to its corner
+            if dx.abs() == 2 {
+                let rook = self.take(m.from + Shift::RIGHT * dx.signum());
+                let corner = Pos::new(if dx > 0 { 7 } else { 0 }, m.from.y());
+                self.place(corner, rook.expect("no rook to castle with"));
+            }
+        }
+
+        if let Some((pos, captured)) = undo.captured {
+            self.place(pos, captured);
+        }
+
+        self.castling = undo.castling;
+        self.en_passant = undo.en_passant;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_number = undo.fullmove_number;
+        self.zobrist = undo.zobrist;
+    }
+
+    /// Performs a move, changing the board state.
+    pub fn perform(&mut self, m: Move) {
+        self.zobrist ^= zobrist::castling(self.castling) ^ self.en_passant_zobrist();
+        let en_passant = self.en_passant.take();
+
+        let piece = self.take(m.from).unwrap();
+        let captured = self.take(m.to);
+
+        if captured.is_some() || piece.kind == Kind::Pawn {
+            self.halfmove_clock = 0;
         } else {
-            match &self[to] {
-                None => true,
-                Some(piece) => {
-                    if piece.color == player {
-                        false
-                    } else {
-                        *capture = true;
-                        true
-                    }
+            self.halfmove_clock = self.halfmove_clock.saturating_add(1);
+        }
+        if self.turn == Color::Black {
+            self.fullmove_number = self.fullmove_number.saturating_add(1);
+        }
+
+        match piece.kind {
+            Kind::King => {
+                self.kings[self.turn.king_index()] = m.to;
+                self.castling[self.turn.king_index()] = Castling::NONE;
+
+                // A king moving two tiles is castling, so the rook jumps over to its other side
+                let dx = m.to.x() - m.from.x();
+                if dx.abs() == 2 {
+                    let rook = self.take(Pos::new(if dx > 0 { 7 } else { 0 }, m.from.y()));
+                    self.place(m.from + Shift::RIGHT * dx.signum(), rook.unwrap());
                 }
             }
+            Kind::Pawn => {
+                if Some(m.to) == en_passant {
+                    // Capturing en passant, the captured pawn is beside the moving pawn
+                    self.take(Pos::new(m.to.x(), m.from.y()));
+                } else if (m.to.y() - m.from.y()).abs() == 2 {
+                    self.en_passant = Some(Pos::new(m.from.x(), (m.from.y() + m.to.y()) / 2));
+                }
+            }
+            _ => {}
+        }
+
+        self.revoke_castling(m.from);
+        self.revoke_castling(m.to);
+        let kind = m.promotion.unwrap_or(piece.kind);
+        self.place(m.to, Piece::new(piece.color, kind));
+        self.turn = !self.turn;
+
+        self.zobrist ^=
+            zobrist::TURN ^ zobrist::castling(self.castling) ^ self.en_passant_zobrist();
+    }
+
+    /// Removes the piece on the given position from the board, if any, and from the bitboards and
+    /// the hash.
+    fn take(&mut self, pos: Pos) -> Option<Piece> {
+        let piece = self.board[pos.y() as usize][pos.x() as usize].take();
+        if let Some(piece) = piece {
+            self.toggle(pos, piece);
+        }
+        piece
+    }
+
+    /// Puts a piece on the given empty position, adding it to the bitboards and the hash.
+    fn place(&mut self, pos: Pos, piece: Piece) {
+        self.board[pos.y() as usize][pos.x() as usize] = Some(piece);
+        self.toggle(pos, piece);
+    }
+
+    /// Adds a piece to or removes it from the bitboards and the hash.
+    fn toggle(&mut self, pos: Pos, piece: Piece) {
+        let tile = Bitboard::from(pos);
+        self.by_kind[piece.kind as usize] ^= tile;
+        self.by_color[piece.color.king_index()] ^= tile;
+        self.zobrist ^= zobrist::piece(piece, pos);
+        if piece.kind == Kind::Pawn {
+            self.pawn_zobrist ^= zobrist::piece(piece, pos);
+        }
+    }
+
+    /// Evaluates how many centipawns a board state is worth. A positive score indicates that white
+    /// is in a favorable position, and a negative score indicates that black is currently better
+    /// off. A finished game scores as a mate or a draw.
+    pub fn evaluate(&self) -> Score {
+        match self.outcome() {
+            None => self.evaluate_pieces(),
+            Some(outcome) => outcome.value(),
+        }
+    }
+
+    /// Removes the castling right that belongs to the rook starting on the given position, if any.
+    /// Called for both ends of every move, since a rook loses its right both by moving and by
+    /// being captured.
+    fn revoke_castling(&mut self, pos: Pos) {
+        for player in [Color::White, Color::Black] {
+            let castling = &mut self.castling[player.king_index()];
+            if pos == Pos::new(0, player.home_row()) {
+                castling.queen_side = false;
+            } else if pos == Pos::new(7, player.home_row()) {
+                castling.king_side = false;
+            }
+        }
+    }
+
+    /// Checks whether the given position is attacked by any of the given player's pieces.
+    fn is_attacked(&self, pos: Pos, by: Color) -> bool {
+        !self.attackers(pos, by, self.occupied()).is_empty()
+    }
+
+    /// Returns the given player's pieces that attack the given position, with the sliders blocked
+    /// by the given tiles rather than the pieces actually on the board.
+    fn attackers(&self, pos: Pos, by: Color, occupied: Bitboard) -> Bitboard {
+        let pieces = |kinds: &[Kind]| {
+            kinds.iter().fold(Bitboard::EMPTY, |pieces, kind| {
+                pieces | self.bitboard(Piece::new(by, *kind))
+            })
+        };
+
+        // Attacks are symmetric, so a piece attacks the target if a piece of the same kind on the
+        // target would attack it. Pawns are the exception, since they attack forwards: they attack
+        // the target if a pawn of the other player on the target would attack them.
+        (bitboard::pawn_attacks(!by, pos) & pieces(&[Kind::Pawn]))
+            | (bitboard::knight_attacks(pos) & pieces(&[Kind::Knight]))
+            | (bitboard::king_attacks(pos) & pieces(&[Kind::King]))
+            | (bitboard::rook_attacks(pos, occupied) & pieces(&[Kind::Rook, Kind::Queen]))
+            | (bitboard::bishop_attacks(pos, occupied) & pieces(&[Kind::Bishop, Kind::Queen]))
+    }
+
+    /// Checks whether neither player can possibly mate, however badly the other plays: only kings
+    /// are left, plus a single knight, or any number of bishops that all move on tiles of the same
+    /// color.
+    fn is_insufficient_material(&self) -> bool {
+        let kinds = |kinds: &[Kind]| {
+            kinds.iter().fold(Bitboard::EMPTY, |tiles, kind| {
+                tiles | self.by_kind[*kind as usize]
+            })
+        };
+        if !kinds(&[Kind::Pawn, Kind::Rook, Kind::Queen]).is_empty() {
+            return false;
+        }
+
+        let knights = kinds(&[Kind::Knight]);
+        let bishops = kinds(&[Kind::Bishop]);
+        match knights.len() {
+            0 => (bishops & Bitboard::LIGHT).is_empty() || (bishops & !Bitboard::LIGHT).is_empty(),
+            1 => bishops.is_empty(),
+            _ => false,
         }
     }
 
     /// Checks whether the given player is currently checked.
-    fn is_checked(&self, player: Color) -> bool {
-        let king = self.kings[player.king_index()];
-        self.unsafe_moves(!player).any(|m| m.to == king)
+    pub fn is_checked(&self, player: Color) -> bool {
+        self.is_attacked(self.kings[player.king_index()], !player)
     }
 
     /// Returns the outcome of the game state. A `None` output indicates that the game is not over,
     /// whereas `Some(Outcome)` indicates which player has won the game, or if there was a
-    /// stalemate.
+    /// stalemate or too little material left to mate.
     pub fn outcome(&self) -> Option<Outcome> {
-        if self.moves().next().is_none() {
+        if self.is_insufficient_material() {
+            return Some(Outcome::Draw(DrawReason::InsufficientMaterial));
+        }
+
+        let mut moves = MoveList::new();
+        self.generate_into(&mut moves);
+        if moves.is_empty() {
             // No legal moves for the current player, the game is over
             if self.is_checked(self.turn) {
                 // The current player is checked, so the other player wins
@@ -258,6 +739,12 @@ impl Chess {
     }
 }
 
+impl Hash for Chess {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.zobrist);
+    }
+}
+
 impl Default for Chess {
     fn default() -> Self {
         Chess::new()
@@ -280,15 +767,6 @@ where
     }
 }
 
-impl<P> IndexMut<P> for Chess
-where
-    P: Borrow<Pos>,
-{
-    fn index_mut(&mut self, index: P) -> &mut Self::Output {
-        &mut self.board[index.borrow().y() as usize][index.borrow().x() as usize]
-    }
-}
-
 #[rustfmt::skip]
 impl Display for Chess {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -386,16 +864,44 @@ impl Display for Chess {
 pub enum Outcome {
     Winner(Color),
     Stalemate,
+    /// A draw for another reason than stalemate. Most of these depend on the moves played, which
+    /// only a [`Game`] knows about.
+    ///
+    /// [`Game`]: crate::game::Game
+    Draw(DrawReason),
 }
 
 impl Outcome {
-    pub fn value(&self) -> i16 {
+    pub fn value(&self) -> Score {
         match self {
-            Outcome::Winner(color) => match color {
-                Color::Black => i16::MIN,
-                Color::White => i16::MAX,
-            },
-            Outcome::Stalemate => 0,
+            Outcome::Winner(color) => Score::mate(*color, 0),
+            Outcome::Stalemate | Outcome::Draw(_) => Score::DRAW,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum DrawReason {
+    /// The same position occurred three times, with the same player to move.
+    ThreefoldRepetition,
+    /// The same position occurred five times, with the same player to move.
+    FivefoldRepetition,
+    /// No captures or pawn moves were made for fifty moves by each player.
+    FiftyMoves,
+    /// No captures or pawn moves were made for seventy-five moves by each player.
+    SeventyFiveMoves,
+    /// Neither player has enough pieces left to mate.
+    InsufficientMaterial,
+}
+
+impl Display for DrawReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DrawReason::ThreefoldRepetition => write!(f, "threefold repetition"),
+            DrawReason::FivefoldRepetition => write!(f, "fivefold repetition"),
+            DrawReason::FiftyMoves => write!(f, "the fifty-move rule"),
+            DrawReason::SeventyFiveMoves => write!(f, "the seventy-five-move rule"),
+            DrawReason::InsufficientMaterial => write!(f, "insufficient material"),
         }
     }
 }
@@ -421,14 +927,24 @@ impl Piece {
     pub const BLACK_QUEEN: Piece = Piece::new(Color::Black, Kind::Queen);
     pub const BLACK_KING: Piece = Piece::new(Color::Black, Kind::King);
 
-    const fn new(color: Color, kind: Kind) -> Self {
+    pub const fn new(color: Color, kind: Kind) -> Self {
         Piece { color, kind }
     }
 
-    pub fn base_value(&self) -> i16 {
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// Returns what the piece is worth in centipawns, like [`Kind::value`], but negative for
+    /// black's pieces.
+    pub fn value(&self) -> Tapered {
         match self.color {
-            Color::Black => -self.kind.base_value(),
-            Color::White => self.kind.base_value(),
+            Color::Black => -self.kind.value(),
+            Color::White => self.kind.value(),
         }
     }
 }
@@ -453,7 +969,7 @@ pub enum Color {
 impl Color {
     /// Checks whether a new game state evaluation is better than the previous best for the current
     /// player.
-    pub fn improves(&self, score: i16, best_score: Option<i16>) -> bool {
+    pub fn improves(&self, score: Score, best_score: Option<Score>) -> bool {
         match best_score {
             None => true,
             Some(best) => match self {
@@ -463,12 +979,38 @@ impl Color {
         }
     }
 
-    fn king_index(&self) -> usize {
+    pub fn king_index(&self) -> usize {
         match self {
             Color::Black => 1,
             Color::White => 0,
         }
     }
+
+    /// Returns the row on which the player's king and rooks start.
+    pub(crate) fn home_row(&self) -> isize {
+        match self {
+            Color::Black => 0,
+            Color::White => 7,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Castling {
+    pub king_side: bool,
+    pub queen_side: bool,
+}
+
+impl Castling {
+    pub const NONE: Castling = Castling {
+        king_side: false,
+        queen_side: false,
+    };
+
+    pub const BOTH: Castling = Castling {
+        king_side: true,
+        queen_side: true,
+    };
 }
 
 impl Not for Color {
@@ -502,16 +1044,44 @@ pub enum Kind {
 }
 
 impl Kind {
-    pub fn base_value(&self) -> i16 {
+    /// The kinds a pawn can promote to, wrapped for use as a [`Move`]'s promotion.
+    const PROMOTIONS: [Option<Kind>; 4] = [
+        Some(Kind::Queen),
+        Some(Kind::Rook),
+        Some(Kind::Bishop),
+        Some(Kind::Knight),
+    ];
+
+    /// Returns the uppercase letter used for this kind in FEN and algebraic notation.
+    pub fn letter(&self) -> char {
         match self {
-            Kind::Pawn => 1,
-            Kind::Rook => 5,
-            Kind::Knight => 3,
-            Kind::Bishop => 3,
-            Kind::Queen => 9,
-            Kind::King => 0,
+            Kind::Pawn => 'P',
+            Kind::Rook => 'R',
+            Kind::Knight => 'N',
+            Kind::Bishop => 'B',
+            Kind::Queen => 'Q',
+            Kind::King => 'K',
+        }
+    }
+
+    /// Parses an uppercase letter as used in FEN and algebraic notation.
+    pub fn from_letter(letter: char) -> Option<Kind> {
+        match letter {
+            'P' => Some(Kind::Pawn),
+            'R' => Some(Kind::Rook),
+            'N' => Some(Kind::Knight),
+            'B' => Some(Kind::Bishop),
+            'Q' => Some(Kind::Queen),
+            'K' => Some(Kind::King),
+            _ => None,
         }
     }
+
+    /// Returns what a piece of this kind is worth in centipawns, in the middlegame and in the
+    /// endgame, regardless of where it stands.
+    pub fn value(&self) -> Tapered {
+        evaluation::VALUES[*self as usize]
+    }
 }
 
 impl Display for Kind {
@@ -527,14 +1097,164 @@ impl Display for Kind {
     }
 }
 
+/// What [`Chess::make_move`] changed that cannot be worked out from the move itself, so that
+/// [`Chess::unmake_move`] can restore it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Undo {
+    m: Move,
+    /// The moving piece, before it was promoted.
+    piece: Piece,
+    /// The captured piece and where it stood, which is not the move's target when capturing en
+    /// passant.
+    captured: Option<(Pos, Piece)>,
+    castling: [Castling; 2],
+    en_passant: Option<Pos>,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+    zobrist: u64,
+}
+
+impl Undo {
+    /// Returns the move that was made.
+    pub fn m(&self) -> Move {
+        self.m
+    }
+
+    /// Returns the piece the move captured, if any.
+    pub fn captured(&self) -> Option<Piece> {
+        self.captured.map(|(_, piece)| piece)
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Move {
     from: Pos,
     to: Pos,
+    /// The kind a pawn is turned into when this move brings it to the last row.
+    promotion: Option<Kind>,
 }
 
 impl Move {
-    fn new(from: Pos, to: Pos) -> Self {
-        Move { from, to }
+    const fn new(from: Pos, to: Pos) -> Self {
+        Move {
+            from,
+            to,
+            promotion: None,
+        }
+    }
+
+    pub fn from(&self) -> Pos {
+        self.from
+    }
+
+    pub fn to(&self) -> Pos {
+        self.to
+    }
+
+    pub fn promotion(&self) -> Option<Kind> {
+        self.promotion
+    }
+
+    /// Writes the move in the long algebraic notation used by UCI, e.g. `e2e4`, `e1g1` for
+    /// castling, or `e7e8q` for a promotion.
+    pub fn to_uci(&self) -> String {
+        let promotion = self
+            .promotion
+            .map(|kind| kind.letter().to_ascii_lowercase().to_string())
+            .unwrap_or_default();
+        format!("{}{}{promotion}", self.from, self.to)
+    }
+
+    /// Parses a move in the long algebraic notation used by UCI. The move is not checked against
+    /// any position, so it should be compared to the legal moves before performing it.
+    pub fn from_uci(uci: &str) -> Option<Move> {
+        let from = Pos::from_algebraic(uci.get(0..2)?)?;
+        let to = Pos::from_algebraic(uci.get(2..4)?)?;
+        let promotion = match &uci[4..] {
+            "" => None,
+            "q" => Some(Kind::Queen),
+            "r" => Some(Kind::Rook),
+            "b" => Some(Kind::Bishop),
+            "n" => Some(Kind::Knight),
+            _ => return None,
+        };
+        Some(Move {
+            from,
+            to,
+            promotion,
+        })
+    }
+}
+
+/// The most legal moves a player can have in any position is 218, so this always leaves room.
+const MAX_MOVES: usize = 256;
+
+/// A list of moves with a fixed capacity, kept on the stack rather than allocated, for
+/// [`Chess::generate_into`]. It dereferences to a slice of the moves in it.
+#[derive(Copy, Clone)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    pub const fn new() -> Self {
+        MoveList {
+            moves: [Move::new(Pos::new(0, 0), Pos::new(0, 0)); MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, m: Move) {
+        self.moves[self.len] = m;
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        MoveList::new()
+    }
+}
+
+impl Debug for MoveList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &Self::Target {
+        &self.moves[..self.len]
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl IntoIterator for MoveList {
+    type Item = Move;
+    type IntoIter = Take<array::IntoIter<Move, MAX_MOVES>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.moves.into_iter().take(self.len)
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
@@ -1,8 +1,112 @@
-use crate::chess::{Chess, Move};
+use std::fmt::{Display, Formatter};
+use std::ops::Neg;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
+use crate::chess::{Chess, Color, Move, MoveList};
+use crate::game::{self, Game};
+use crate::pawns::PawnTable;
+use crate::transposition::{Bound, Entry, TranspositionTable};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct BestMove {
     pub m: Option<Move>,
-    score: i16,
+    pub score: Score,
+}
+
+/// How good a position is for white, in centipawns, i.e. hundredths of a pawn. Negative scores
+/// are good for black.
+///
+/// A won game scores higher than any evaluation, minus the number of moves (plies) it takes to get
+/// there, so that a quicker mate is preferred over a slower one. Likewise, a lost game scores lower
+/// than any evaluation, plus the number of moves it takes, so that the losing player holds out as
+/// long as possible.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Score(i16);
+
+impl Score {
+    /// The score of a drawn game.
+    pub const DRAW: Score = Score(0);
+    /// White mates right away, which is better for white than any other score.
+    pub const MAX: Score = Score(MATE);
+    /// Black mates right away, which is better for black than any other score.
+    pub const MIN: Score = Score(-MATE);
+
+    /// The score of an evaluation in centipawns. Evaluations beyond the scores of mates are
+    /// clamped, so that they cannot be mistaken for one.
+    pub const fn centipawns(centipawns: i16) -> Self {
+        if centipawns > MAX_EVALUATION {
+            Score(MAX_EVALUATION)
+        } else if centipawns < -MAX_EVALUATION {
+            Score(-MAX_EVALUATION)
+        } else {
+            Score(centipawns)
+        }
+    }
+
+    /// The score of `winner` mating after the given number of moves (plies) by either player.
+    pub fn mate(winner: Color, plies: u16) -> Self {
+        let score = Score(MATE - plies.min(MAX_MATE_PLIES) as i16);
+        match winner {
+            Color::Black => -score,
+            Color::White => score,
+        }
+    }
+
+    /// Returns the number of moves by the winning player until mate, positive if white wins and
+    /// negative if black wins, or `None` if the score is not a mate.
+    pub fn mate_in(&self) -> Option<i16> {
+        let plies = MATE - self.0.abs();
+        (plies <= MAX_MATE_PLIES as i16).then(|| self.0.signum() * (plies + 1) / 2)
+    }
+
+    /// Moves a mate score the given number of moves away, e.g. from the position the mate was found
+    /// in to the root of the search.
+    fn later(self, plies: usize) -> Self {
+        match self.mate_in() {
+            Some(_) => Score(self.0 - self.0.signum() * plies as i16),
+            None => self,
+        }
+    }
+
+    /// Moves a mate score the given number of moves closer, undoing [`later`].
+    ///
+    /// [`later`]: #method.later
+    fn earlier(self, plies: usize) -> Self {
+        match self.mate_in() {
+            Some(_) => Score(self.0 + self.0.signum() * plies as i16),
+            None => self,
+        }
+    }
+}
+
+/// Higher than any evaluation, and far enough from `i16::MAX` that [`Score::MIN`] can be negated.
+const MATE: i16 = 30_000;
+
+/// The longest distance to mate that is counted, which no search will ever get near.
+const MAX_MATE_PLIES: u16 = 1_000;
+
+/// The highest score that is not a mate.
+const MAX_EVALUATION: i16 = MATE - MAX_MATE_PLIES as i16 - 1;
+
+/// Flips the score to the other player's point of view.
+impl Neg for Score {
+    type Output = Score;
+
+    fn neg(self) -> Self::Output {
+        Score(-self.0)
+    }
+}
+
+/// Formats the score as in the Universal Chess Interface: `cp` followed by the score in
+/// centipawns, or `mate` followed by the number of moves until mate.
+impl Display for Score {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.mate_in() {
+            Some(moves) => write!(f, "mate {moves}"),
+            None => write!(f, "cp {}", self.0),
+        }
+    }
 }
 
 pub fn minimax(chess: &Chess, depth: u8) -> BestMove {
@@ -18,7 +122,7 @@ pub fn minimax(chess: &Chess, depth: u8) -> BestMove {
         for m in chess.moves() {
             let mut copy = *chess;
             copy.perform(m);
-            let score = minimax(&copy, depth - 1).score;
+            let score = minimax(&copy, depth - 1).score.later(1);
             if chess.turn.improves(score, best_score) {
                 best_score = Some(score);
                 best_move = Some(m);
@@ -31,3 +135,445 @@ pub fn minimax(chess: &Chess, depth: u8) -> BestMove {
         }
     }
 }
+
+/// Finds the same best move as [`minimax`], but stops looking at a position's remaining moves as
+/// soon as one of them shows that the opponent would never allow that position.
+///
+/// Like [`minimax`], and unlike [`iterative_deepening`], this does not score repeated positions
+/// as a draw, so that the two can be checked against each other.
+pub fn alpha_beta(chess: &Chess, depth: u8) -> BestMove {
+    Searcher::new(None, None, SearchLimits::default(), None, false, false)
+        .root(&Game::from(*chess), depth)
+}
+
+/// Finds the best move like [`alpha_beta`], but stores the result of every searched position in
+/// the given table. Positions found in it are not searched again, and the best move stored for a
+/// position is tried first, which makes it more likely that the remaining moves can be skipped.
+pub fn alpha_beta_with_table(chess: &Chess, depth: u8, table: &mut TranspositionTable) -> BestMove {
+    Searcher::new(
+        Some(table),
+        None,
+        SearchLimits::default(),
+        None,
+        false,
+        false,
+    )
+    .root(&Game::from(*chess), depth)
+}
+
+/// Finds the best move like [`alpha_beta`], but positions at the given depth are not evaluated
+/// right away while captures are still possible. Those captures are searched first, until the
+/// position is quiet. [`iterative_deepening`] always searches this way.
+pub fn alpha_beta_with_quiescence(chess: &Chess, depth: u8) -> BestMove {
+    Searcher::new(None, None, SearchLimits::default(), None, true, false)
+        .root(&Game::from(*chess), depth)
+}
+
+/// Limits on how long [`iterative_deepening`] may search. Every limit that is set applies, and a
+/// search without any limits only ends when it is stopped.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SearchLimits {
+    /// The maximum number of moves to look ahead.
+    pub depth: Option<u8>,
+    /// The maximum number of positions to visit.
+    pub nodes: Option<u64>,
+    /// The exact time to spend on this move.
+    pub move_time: Option<Duration>,
+    /// The time left on the current player's clock, of which a share is spent on this move.
+    pub clock: Option<Duration>,
+    /// The time added to the current player's clock after this move.
+    pub increment: Duration,
+}
+
+impl SearchLimits {
+    /// Returns how long the search may take, if it is limited by time at all. With a clock, a
+    /// small share of the remaining time is used, plus most of the increment.
+    pub fn time(&self) -> Option<Duration> {
+        self.move_time.or_else(|| {
+            self.clock.map(|clock| {
+                (clock / 30 + self.increment * 3 / 4).min(clock.saturating_sub(SAFETY_MARGIN))
+            })
+        })
+    }
+}
+
+/// Time kept in reserve on the clock for the overhead of communicating a move.
+const SAFETY_MARGIN: Duration = Duration::from_millis(50);
+
+/// What [`iterative_deepening`] found out about a position.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SearchResult {
+    /// The principal variation: the best move, followed by the moves both players are expected
+    /// to play after it.
+    pub pv: Vec<Move>,
+    pub score: Score,
+    /// The depth of the deepest search that completed.
+    pub depth: u8,
+    /// The number of positions visited by all searches together.
+    pub nodes: u64,
+    /// The time spent searching.
+    pub time: Duration,
+}
+
+impl SearchResult {
+    /// Returns the move to play, or `None` if the game is already over.
+    pub fn best_move(&self) -> Option<Move> {
+        self.pv.first().copied()
+    }
+}
+
+/// Searches one move deeper at a time until one of the limits is reached or `stop` is set, and
+/// returns the result of the last search that completed. After every completed depth, `report`
+/// is called with its result.
+///
+/// Each search tries the moves of the previous search's principal variation first, i.e. the moves
+/// both players were expected to play, since they are likely to still be good one move deeper.
+///
+/// Positions that already occurred in the game or earlier in the search score as a draw, since
+/// either player could repeat the moves that led back to them.
+///
+/// Like `table`, the structures of the pawns stored in `pawns` are kept for later searches.
+pub fn iterative_deepening(
+    game: &Game,
+    limits: SearchLimits,
+    table: &mut TranspositionTable,
+    pawns: &mut PawnTable,
+    stop: &AtomicBool,
+    mut report: impl FnMut(&SearchResult),
+) -> SearchResult {
+    let chess = game.chess();
+    let mut searcher = Searcher::new(Some(table), Some(pawns), limits, Some(stop), true, true);
+
+    // Without time for a single search, any legal move is better than none
+    let mut result = SearchResult {
+        pv: chess.moves().take(1).collect(),
+        score: chess.evaluate(),
+        depth: 0,
+        nodes: 0,
+        time: Duration::ZERO,
+    };
+
+    for depth in 1..=limits.depth.unwrap_or(u8::MAX) {
+        let best = searcher.root(game, depth);
+        result.nodes = searcher.nodes;
+        result.time = searcher.start.elapsed();
+        if searcher.aborted {
+            break;
+        }
+        result.pv = searcher.pv[0].clone();
+        result.score = best.score;
+        result.depth = depth;
+        report(&result);
+
+        // The next search takes several times as long as this one, so it is not started when it
+        // is unlikely to finish in time
+        if best.m.is_none() || searcher.time.is_some_and(|time| result.time * 2 > time) {
+            break;
+        }
+        searcher.previous_pv = result.pv.clone();
+    }
+
+    result
+}
+
+/// The state shared by all positions visited during a search.
+struct Searcher<'a> {
+    table: Option<&'a mut TranspositionTable>,
+    limits: SearchLimits,
+    stop: Option<&'a AtomicBool>,
+    start: Instant,
+    time: Option<Duration>,
+    nodes: u64,
+    /// Set once a limit is reached, after which the results of the search are meaningless.
+    aborted: bool,
+    /// The principal variation found at each ply, built up from the deepest ply upwards. The
+    /// variation at index 0 is the one of the whole search.
+    pv: Vec<Vec<Move>>,
+    /// The principal variation of the previous search, to be tried first.
+    previous_pv: Vec<Move>,
+    /// Whether to resolve captures before evaluating positions at the maximum depth.
+    quiescence: bool,
+    /// Whether positions that occurred before score as a draw.
+    repetitions: bool,
+    /// The hashes of the positions before the current one, in the game and in the search.
+    positions: Vec<u64>,
+    /// The structures of the pawns found in earlier positions, if they are remembered.
+    pawns: Option<&'a mut PawnTable>,
+}
+
+impl<'a> Searcher<'a> {
+    fn new(
+        table: Option<&'a mut TranspositionTable>,
+        pawns: Option<&'a mut PawnTable>,
+        limits: SearchLimits,
+        stop: Option<&'a AtomicBool>,
+        quiescence: bool,
+        repetitions: bool,
+    ) -> Self {
+        Searcher {
+            table,
+            limits,
+            stop,
+            start: Instant::now(),
+            time: limits.time(),
+            nodes: 0,
+            aborted: false,
+            pv: Vec::new(),
+            previous_pv: Vec::new(),
+            quiescence,
+            repetitions,
+            positions: Vec::new(),
+            pawns,
+        }
+    }
+
+    fn root(&mut self, game: &Game, depth: u8) -> BestMove {
+        self.pv.resize(depth as usize + 1, Vec::new());
+        self.positions.clear();
+        self.positions.extend_from_slice(game.hashes());
+        // The search makes and takes back moves in place on its own copy of the position
+        let mut chess = *game.chess();
+        self.alpha_beta(&mut chess, depth, 0, Score::MIN, Score::MAX, true)
+    }
+
+    /// Evaluates a position like [`Chess::evaluate`], looking up the pawn structure in the table if
+    /// there is one.
+    fn evaluate(&mut self, chess: &Chess) -> Score {
+        match self.pawns.as_deref_mut() {
+            Some(pawns) => chess.evaluate_with_table(pawns),
+            None => chess.evaluate(),
+        }
+    }
+
+    /// Evaluates a position like [`Chess::evaluate_pieces`], looking up the pawn structure in the
+    /// table if there is one.
+    fn evaluate_pieces(&mut self, chess: &Chess) -> Score {
+        match self.pawns.as_deref_mut() {
+            Some(pawns) => chess.evaluate_pieces_with_table(pawns),
+            None => chess.evaluate_pieces(),
+        }
+    }
+
+    /// Checks whether any of the limits has been reached. The clock and the stop flag are only
+    /// looked at every so many positions, since that takes longer than visiting one.
+    fn should_abort(&mut self) -> bool {
+        if !self.aborted {
+            self.aborted = self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes)
+                || (self.nodes.is_multiple_of(1024)
+                    && (self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed))
+                        || self.time.is_some_and(|time| self.start.elapsed() >= time)));
+        }
+        self.aborted
+    }
+
+    /// Searches a position `ply` moves away from the root. `alpha` is the score white is already
+    /// guaranteed elsewhere in the tree, and `beta` the score black is already guaranteed. Once
+    /// they cross, the current position cannot influence the result, so its remaining moves are
+    /// skipped. `on_pv` tells whether all moves leading here follow the previous principal
+    /// variation.
+    fn alpha_beta(
+        &mut self,
+        chess: &mut Chess,
+        depth: u8,
+        ply: usize,
+        mut alpha: Score,
+        mut beta: Score,
+        on_pv: bool,
+    ) -> BestMove {
+        self.nodes += 1;
+        self.pv[ply].clear();
+
+        // Whoever can avoid the repetition would have done so if it were bad for them
+        if self.repetitions && ply > 0 && game::earlier_occurrences(&self.positions, chess) > 0 {
+            return BestMove {
+                m: None,
+                score: Score::DRAW,
+            };
+        }
+
+        // A game that is over is never searched any further, so that e.g. a stalemate at the
+        // maximum depth is not mistaken for a won position by the quiescence search
+        if (depth == 0 && !self.quiescence) || chess.outcome().is_some() || self.should_abort() {
+            return BestMove {
+                m: None,
+                score: self.evaluate(chess).later(ply),
+            };
+        }
+        if depth == 0 {
+            return BestMove {
+                m: None,
+                score: self.quiescence(chess, ply, alpha, beta),
+            };
+        }
+
+        let entry = self
+            .table
+            .as_mut()
+            .and_then(|table| table.get(chess.zobrist()));
+        if let Some(entry) = entry.filter(|entry| entry.depth >= depth && ply > 0) {
+            // Mates are stored counting from the position itself, rather than from the root
+            let score = entry.score.later(ply);
+            let usable = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+            if usable {
+                self.pv[ply].extend(entry.best_move);
+                return BestMove {
+                    m: entry.best_move,
+                    score,
+                };
+            }
+        }
+
+        let (alpha_before, beta_before) = (alpha, beta);
+        let mut best_move = None;
+        let mut best_score = None;
+
+        let pv_move = self.previous_pv.get(ply).copied().filter(|_| on_pv);
+        let stored_move = entry.and_then(|entry| entry.best_move);
+        let first = [pv_move, stored_move.filter(|m| Some(*m) != pv_move)];
+        let mut rest = MoveList::new();
+        chess.generate_into(&mut rest);
+        let moves = first.into_iter().flatten().chain(
+            rest.iter()
+                .copied()
+                .filter(|m| Some(*m) != pv_move && Some(*m) != stored_move),
+        );
+
+        self.positions.push(chess.zobrist());
+        for m in moves {
+            let undo = chess.make_move(m);
+            let on_pv = on_pv && Some(m) == pv_move;
+            let score = self
+                .alpha_beta(chess, depth - 1, ply + 1, alpha, beta, on_pv)
+                .score;
+            chess.unmake_move(undo);
+            if self.aborted {
+                break;
+            }
+
+            if chess.turn.improves(score, best_score) {
+                best_score = Some(score);
+                best_move = Some(m);
+
+                let (pv, rest) = self.pv.split_at_mut(ply + 1);
+                pv[ply].clear();
+                pv[ply].push(m);
+                pv[ply].extend_from_slice(&rest[0]);
+            }
+
+            match chess.turn {
+                Color::Black => beta = beta.min(score),
+                Color::White => alpha = alpha.max(score),
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        self.positions.pop();
+
+        let Some(score) = best_score.filter(|_| !self.aborted) else {
+            return BestMove {
+                m: best_move,
+                score: self.evaluate(chess).later(ply),
+            };
+        };
+
+        if let Some(table) = self.table.as_mut() {
+            let bound = if score <= alpha_before {
+                Bound::Upper
+            } else if score >= beta_before {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            table.insert(Entry {
+                zobrist: chess.zobrist(),
+                depth,
+                score: score.earlier(ply),
+                bound,
+                best_move,
+            });
+        }
+
+        BestMove {
+            m: best_move,
+            score,
+        }
+    }
+
+    /// Keeps searching captures and promotions beyond the maximum depth, until the position is
+    /// quiet. Evaluating a position halfway through an exchange would make the search believe
+    /// that e.g. taking a defended pawn with a queen wins a pawn, since it does not see the queen
+    /// being taken back.
+    ///
+    /// The current player does not have to capture, so the evaluation of the position itself
+    /// (the "stand pat" score) is a lower bound for them. If it already exceeds what the opponent
+    /// is guaranteed elsewhere, the captures do not have to be searched at all. A player in check
+    /// has no such choice, so all of their moves out of check are searched instead.
+    fn quiescence(
+        &mut self,
+        chess: &mut Chess,
+        ply: usize,
+        mut alpha: Score,
+        mut beta: Score,
+    ) -> Score {
+        self.nodes += 1;
+
+        if self.should_abort() {
+            return self.evaluate_pieces(chess);
+        }
+
+        let mut moves = MoveList::new();
+        let mut best_score = if chess.is_checked(chess.turn) {
+            chess.generate_into(&mut moves);
+            if moves.is_empty() {
+                return self.evaluate(chess).later(ply);
+            }
+            None
+        } else {
+            let stand_pat = self.evaluate_pieces(chess);
+            match chess.turn {
+                Color::Black => beta = beta.min(stand_pat),
+                Color::White => alpha = alpha.max(stand_pat),
+            }
+            if alpha >= beta {
+                return stand_pat;
+            }
+            chess.generate_captures_into(&mut moves);
+            Some(stand_pat)
+        };
+
+        // Taking the most valuable pieces with the least valuable ones first finds cut-offs soonest
+        moves.sort_unstable_by_key(|m| {
+            let victim = chess[m.to()].map_or(0, |piece| piece.kind().value().middlegame);
+            let attacker = chess[m.from()].map_or(0, |piece| piece.kind().value().middlegame);
+            (-victim, attacker)
+        });
+
+        for &m in &moves {
+            let undo = chess.make_move(m);
+            let score = self.quiescence(chess, ply + 1, alpha, beta);
+            chess.unmake_move(undo);
+            if self.aborted {
+                break;
+            }
+
+            if chess.turn.improves(score, best_score) {
+                best_score = Some(score);
+            }
+
+            match chess.turn {
+                Color::Black => beta = beta.min(score),
+                Color::White => alpha = alpha.max(score),
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best_score.unwrap_or_else(|| self.evaluate_pieces(chess))
+    }
+}
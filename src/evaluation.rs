@@ -0,0 +1,303 @@
+//! Judging a position by the pieces on the board: what they are worth, and how much better they
+//! stand on some tiles than on others.
+//!
+//! What makes a position good changes as pieces are traded: the king that hides behind its pawns
+//! while queens and rooks are around should come out and help once they are gone. So every part
+//! of the evaluation has a score for the middlegame and one for the endgame, which are blended by
+//! how much material is left.
+
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+use crate::activity::activity;
+use crate::chess::{Chess, Color, Kind, Piece};
+use crate::computer::Score;
+use crate::king_safety::king_safety;
+use crate::pawns::{PawnStructure, PawnTable};
+use crate::pos::Pos;
+
+/// The [`phase`] of a game in which no pieces but pawns and kings were captured yet.
+///
+/// [`phase`]: crate::chess::Chess::phase
+pub const MAX_PHASE: u8 = 24;
+
+impl Chess {
+    /// Evaluates the board state like [`evaluate`], but only by the pieces on the board, without
+    /// checking whether the game is over. This is much faster, since it does not need to generate
+    /// any moves.
+    ///
+    /// [`evaluate`]: #method.evaluate
+    pub fn evaluate_pieces(&self) -> Score {
+        self.evaluate_structure(&PawnStructure::new(self))
+    }
+
+    /// Evaluates the board state like [`evaluate`], but looks up the pawn structure in the given
+    /// table rather than evaluating it again.
+    ///
+    /// [`evaluate`]: #method.evaluate
+    pub fn evaluate_with_table(&self, pawns: &mut PawnTable) -> Score {
+        match self.outcome() {
+            None => self.evaluate_pieces_with_table(pawns),
+            Some(outcome) => outcome.value(),
+        }
+    }
+
+    /// Evaluates the board state like [`evaluate_pieces`], but looks up the pawn structure in the
+    /// given table rather than evaluating it again.
+    ///
+    /// [`evaluate_pieces`]: #method.evaluate_pieces
+    pub fn evaluate_pieces_with_table(&self, pawns: &mut PawnTable) -> Score {
+        self.evaluate_structure(&pawns.get(self))
+    }
+
+    fn evaluate_structure(&self, pawns: &PawnStructure) -> Score {
+        let pieces: Tapered = self
+            .pieces()
+            .map(|(pos, piece)| piece_value(piece, pos))
+            .sum();
+        let score = pieces + pawns.score + pawns.blocked(self) + king_safety(self) + activity(self);
+        Score::centipawns(score.blend(self.phase()))
+    }
+
+    /// Returns how far the game is from the endgame, by the pieces other than pawns and kings
+    /// that are left: from [`MAX_PHASE`] when none were captured, down to 0 when all of them were.
+    /// Knights and bishops count once, rooks twice and queens four times.
+    pub fn phase(&self) -> u8 {
+        let count = |kind: Kind| self.by_kind[kind as usize].len();
+        let phase = count(Kind::Knight)
+            + count(Kind::Bishop)
+            + 2 * count(Kind::Rook)
+            + 4 * count(Kind::Queen);
+        // Promotions can bring more pieces onto the board than there were at the start
+        phase.min(MAX_PHASE as u32) as u8
+    }
+}
+
+/// A score in centipawns for the middlegame and one for the endgame, positive for white and
+/// negative for black.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Tapered {
+    pub middlegame: i16,
+    pub endgame: i16,
+}
+
+impl Tapered {
+    pub const fn new(middlegame: i16, endgame: i16) -> Self {
+        Tapered {
+            middlegame,
+            endgame,
+        }
+    }
+
+    /// Blends the scores by the [`phase`] of the game, from only the endgame score at phase 0 to
+    /// only the middlegame score at [`MAX_PHASE`].
+    ///
+    /// [`phase`]: crate::chess::Chess::phase
+    pub fn blend(&self, phase: u8) -> i16 {
+        let phase = phase.min(MAX_PHASE) as i32;
+        let blended = (self.middlegame as i32 * phase
+            + self.endgame as i32 * (MAX_PHASE as i32 - phase))
+            / MAX_PHASE as i32;
+        blended as i16
+    }
+}
+
+impl Add for Tapered {
+    type Output = Tapered;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Tapered::new(self.middlegame + rhs.middlegame, self.endgame + rhs.endgame)
+    }
+}
+
+impl AddAssign for Tapered {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Tapered {
+    type Output = Tapered;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Tapered::new(self.middlegame - rhs.middlegame, self.endgame - rhs.endgame)
+    }
+}
+
+impl Mul<i16> for Tapered {
+    type Output = Tapered;
+
+    fn mul(self, rhs: i16) -> Self::Output {
+        Tapered::new(self.middlegame * rhs, self.endgame * rhs)
+    }
+}
+
+impl Neg for Tapered {
+    type Output = Tapered;
+
+    fn neg(self) -> Self::Output {
+        Tapered::new(-self.middlegame, -self.endgame)
+    }
+}
+
+impl Sum for Tapered {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Tapered::default(), Add::add)
+    }
+}
+
+/// Returns what a piece on the given tile is worth, positive for white and negative for black.
+pub fn piece_value(piece: Piece, pos: Pos) -> Tapered {
+    let kind = piece.kind() as usize;
+    let square = square(piece.color(), pos);
+    let value = piece.kind().value()
+        + Tapered::new(
+            MIDDLEGAME_TABLES[kind][square],
+            ENDGAME_TABLES[kind][square],
+        );
+    match piece.color() {
+        Color::Black => -value,
+        Color::White => value,
+    }
+}
+
+/// Returns the index into the tables for a piece of the given color. The tables are laid out as
+/// seen from white's side of the board, so black's pieces look them up upside down.
+fn square(color: Color, pos: Pos) -> usize {
+    let row = match color {
+        Color::Black => 7 - pos.y(),
+        Color::White => pos.y(),
+    };
+    (row * 8 + pos.x()) as usize
+}
+
+/// What every kind of piece is worth, indexed by [`Kind`]. Pawns and rooks gain value as the
+/// board empties, knights lose some. The king is never captured, so it has no value.
+pub(crate) const VALUES: [Tapered; 6] = [
+    Tapered::new(100, 120),
+    Tapered::new(500, 530),
+    Tapered::new(320, 300),
+    Tapered::new(330, 330),
+    Tapered::new(900, 920),
+    Tapered::new(0, 0),
+];
+
+/// Bonuses and penalties in centipawns for every kind of piece on every tile in the middlegame,
+/// indexed by [`Kind`]. The tables are laid out like the board as white sees it, with the eighth
+/// row first.
+const MIDDLEGAME_TABLES: [[i16; 64]; 6] = [
+    PAWN_MIDDLEGAME,
+    ROOK,
+    KNIGHT,
+    BISHOP,
+    QUEEN,
+    KING_MIDDLEGAME,
+];
+
+/// Bonuses and penalties for every kind of piece on every tile in the endgame, like
+/// [`MIDDLEGAME_TABLES`].
+const ENDGAME_TABLES: [[i16; 64]; 6] = [PAWN_ENDGAME, ROOK, KNIGHT, BISHOP, QUEEN, KING_ENDGAME];
+
+/// Pawns should advance, especially in the center, and not leave the king's shelter.
+#[rustfmt::skip]
+const PAWN_MIDDLEGAME: [i16; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     10,  10,  20,  30,  30,  20,  10,  10,
+      5,   5,  10,  25,  25,  10,   5,   5,
+      0,   0,   0,  20,  20,   0,   0,   0,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      5,  10,  10, -20, -20,  10,  10,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+/// Once the pieces that could stop them are gone, pawns race to promote.
+#[rustfmt::skip]
+const PAWN_ENDGAME: [i16; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     80,  80,  80,  80,  80,  80,  80,  80,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     30,  30,  30,  30,  30,  30,  30,  30,
+     20,  20,  20,  20,  20,  20,  20,  20,
+     10,  10,  10,  10,  10,  10,  10,  10,
+     10,  10,  10,  10,  10,  10,  10,  10,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+/// Rooks belong on the seventh row, and in the center of the first.
+#[rustfmt::skip]
+const ROOK: [i16; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10,  10,  10,  10,  10,   5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+      0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+/// Knights reach the fewest tiles from the edges.
+#[rustfmt::skip]
+const KNIGHT: [i16; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+/// Bishops avoid the corners and edges.
+#[rustfmt::skip]
+const BISHOP: [i16; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+/// Queens slightly prefer the center.
+#[rustfmt::skip]
+const QUEEN: [i16; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+/// Kings hide behind their pawns, preferably after castling.
+#[rustfmt::skip]
+const KING_MIDDLEGAME: [i16; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+/// Kings come to the center to support their pawns and stop the opponent's.
+#[rustfmt::skip]
+const KING_ENDGAME: [i16; 64] = [
+    -50, -40, -30, -20, -20, -30, -40, -50,
+    -30, -20, -10,   0,   0, -10, -20, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -30,   0,   0,   0,   0, -30, -30,
+    -50, -30, -30, -30, -30, -30, -30, -50,
+];
@@ -0,0 +1,295 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::bitboard::Bitboard;
+use crate::chess::{Castling, Chess, Color, Kind, Piece};
+use crate::pos::{Pos, Shift};
+
+/// The position at the start of a game, in Forsyth-Edwards Notation.
+pub const STARTING_POSITION: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum FenError {
+    /// The piece placement, side to move, castling, or en passant field is missing.
+    MissingField,
+    /// There are more than six fields.
+    TrailingField,
+    /// The piece placement does not describe exactly eight rows of eight tiles.
+    InvalidBoard,
+    /// The piece placement contains a character that is not a piece or a digit.
+    InvalidPiece(char),
+    /// The given player does not have exactly one king on the board.
+    InvalidKings(Color),
+    /// A pawn stands on the first or eighth row, which pawns never reach without promoting.
+    InvalidPawn,
+    /// The side to move is not `w` or `b`.
+    InvalidTurn,
+    /// The castling field contains something other than `-` or a combination of `KQkq`.
+    InvalidCastling,
+    /// A player has the right to castle, but their king or the rook for that side is not on its
+    /// starting tile.
+    ImpossibleCastling,
+    /// The en passant field is not `-` or a tile that the other player's pawn just leaped over.
+    InvalidEnPassant,
+    /// The player who is not to move is in check, so their king could be captured.
+    InvalidCheck,
+    /// The halfmove clock or fullmove number is not a number.
+    InvalidCounter,
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FenError::MissingField => write!(f, "missing field"),
+            FenError::TrailingField => write!(f, "too many fields"),
+            FenError::InvalidBoard => write!(f, "piece placement must have 8 rows of 8 tiles"),
+            FenError::InvalidPiece(c) => write!(f, "invalid piece '{c}'"),
+            FenError::InvalidKings(color) => write!(f, "{color} must have exactly one king"),
+            FenError::InvalidPawn => write!(f, "pawns cannot stand on the first or eighth row"),
+            FenError::InvalidTurn => write!(f, "side to move must be 'w' or 'b'"),
+            FenError::InvalidCastling => write!(f, "invalid castling rights"),
+            FenError::ImpossibleCastling => {
+                write!(
+                    f,
+                    "castling needs the king and rook on their starting tiles"
+                )
+            }
+            FenError::InvalidEnPassant => write!(f, "invalid en passant tile"),
+            FenError::InvalidCheck => write!(f, "the player not to move is in check"),
+            FenError::InvalidCounter => write!(f, "invalid move counter"),
+        }
+    }
+}
+
+impl Error for FenError {}
+
+impl Chess {
+    /// Parses a position in Forsyth-Edwards Notation. The halfmove clock and fullmove number may
+    /// be left out, in which case they default to 0 and 1.
+    pub fn from_fen(fen: &str) -> Result<Chess, FenError> {
+        let mut fields = fen.split_whitespace();
+        let mut field = || fields.next().ok_or(FenError::MissingField);
+
+        let board = parse_board(field()?)?;
+
+        let turn = match field()? {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(FenError::InvalidTurn),
+        };
+
+        let castling = parse_castling(field()?)?;
+
+        let en_passant = match field()? {
+            "-" => None,
+            tile => Some(Pos::from_algebraic(tile).ok_or(FenError::InvalidEnPassant)?),
+        };
+
+        let mut counter = |default| {
+            fields
+                .next()
+                .map_or(Ok(default), |counter| counter.parse())
+                .map_err(|_| FenError::InvalidCounter)
+        };
+        let halfmove_clock = counter(0)?;
+        let fullmove_number = counter(1)?;
+
+        if fields.next().is_some() {
+            return Err(FenError::TrailingField);
+        }
+
+        let kings = [
+            find_king(&board, Color::White)?,
+            find_king(&board, Color::Black)?,
+        ];
+
+        let mut chess = Chess {
+            board,
+            turn,
+            kings,
+            castling,
+            en_passant,
+            halfmove_clock,
+            fullmove_number,
+            by_kind: [Bitboard::EMPTY; 6],
+            by_color: [Bitboard::EMPTY; 2],
+            zobrist: 0,
+            pawn_zobrist: 0,
+        };
+        chess.fill_bitboards();
+        if !has_castling_pieces(&chess) {
+            return Err(FenError::ImpossibleCastling);
+        }
+        if chess
+            .en_passant
+            .is_some_and(|pos| !is_leaped_over(&chess, pos))
+        {
+            return Err(FenError::InvalidEnPassant);
+        }
+        if chess.is_checked(!turn) {
+            return Err(FenError::InvalidCheck);
+        }
+        chess.zobrist = chess.compute_zobrist();
+        chess.pawn_zobrist = chess.compute_pawn_zobrist();
+        Ok(chess)
+    }
+
+    /// Writes the position in Forsyth-Edwards Notation.
+    pub fn to_fen(&self) -> String {
+        let board = self
+            .board
+            .iter()
+            .map(|row| {
+                let mut row_fen = String::new();
+                let mut empty = 0;
+                for tile in row {
+                    match tile {
+                        None => empty += 1,
+                        Some(piece) => {
+                            if empty > 0 {
+                                row_fen.push_str(&empty.to_string());
+                                empty = 0;
+                            }
+                            row_fen.push(piece_char(*piece));
+                        }
+                    }
+                }
+                if empty > 0 {
+                    row_fen.push_str(&empty.to_string());
+                }
+                row_fen
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let turn = match self.turn {
+            Color::White => "w",
+            Color::Black => "b",
+        };
+
+        let [white, black] = self.castling;
+        let castling: String = [
+            (white.king_side, 'K'),
+            (white.queen_side, 'Q'),
+            (black.king_side, 'k'),
+            (black.queen_side, 'q'),
+        ]
+        .into_iter()
+        .filter_map(|(allowed, c)| allowed.then_some(c))
+        .collect();
+        let castling = if castling.is_empty() {
+            "-".to_owned()
+        } else {
+            castling
+        };
+
+        let en_passant = self
+            .en_passant
+            .map(|pos| pos.to_algebraic())
+            .unwrap_or_else(|| "-".to_owned());
+
+        format!(
+            "{board} {turn} {castling} {en_passant} {} {}",
+            self.halfmove_clock, self.fullmove_number
+        )
+    }
+}
+
+fn parse_board(placement: &str) -> Result<[[Option<Piece>; 8]; 8], FenError> {
+    let mut board = [[None; 8]; 8];
+
+    let rows: Vec<&str> = placement.split('/').collect();
+    if rows.len() != 8 {
+        return Err(FenError::InvalidBoard);
+    }
+
+    for (y, row) in rows.into_iter().enumerate() {
+        let mut x = 0;
+        for c in row.chars() {
+            if let Some(empty) = c.to_digit(10).filter(|empty| (1..=8).contains(empty)) {
+                x += empty as usize;
+            } else {
+                let piece = parse_piece(c).ok_or(FenError::InvalidPiece(c))?;
+                if piece.kind() == Kind::Pawn && (y == 0 || y == 7) {
+                    return Err(FenError::InvalidPawn);
+                }
+                *board[y].get_mut(x).ok_or(FenError::InvalidBoard)? = Some(piece);
+                x += 1;
+            }
+        }
+        if x != 8 {
+            return Err(FenError::InvalidBoard);
+        }
+    }
+
+    Ok(board)
+}
+
+/// Checks whether a pawn of the player who just moved can have leaped over the given tile: the
+/// pawn stands beyond it, and both the tile and the one the pawn came from are empty.
+fn is_leaped_over(chess: &Chess, pos: Pos) -> bool {
+    let (row, leap) = match chess.turn {
+        Color::Black => (5, Shift::UP),
+        Color::White => (2, Shift::DOWN),
+    };
+    pos.y() == row
+        && chess[pos].is_none()
+        && chess[pos - leap].is_none()
+        && chess[pos + leap] == Some(Piece::new(!chess.turn, Kind::Pawn))
+}
+
+/// Checks whether every player's king, and the rook of every side they may castle to, are still
+/// on their starting tiles, since moving either of them takes away the right.
+fn has_castling_pieces(chess: &Chess) -> bool {
+    [Color::White, Color::Black].into_iter().all(|player| {
+        let rights = chess.castling[player.king_index()];
+        let row = player.home_row();
+        let rook = |x| chess[Pos::new(x, row)] == Some(Piece::new(player, Kind::Rook));
+        let king = chess[Pos::new(4, row)] == Some(Piece::new(player, Kind::King));
+        (!rights.king_side || (king && rook(7))) && (!rights.queen_side || (king && rook(0)))
+    })
+}
+
+fn parse_castling(castling: &str) -> Result<[Castling; 2], FenError> {
+    let mut rights = [Castling::NONE; 2];
+    if castling != "-" {
+        for c in castling.chars() {
+            match c {
+                'K' => rights[Color::White.king_index()].king_side = true,
+                'Q' => rights[Color::White.king_index()].queen_side = true,
+                'k' => rights[Color::Black.king_index()].king_side = true,
+                'q' => rights[Color::Black.king_index()].queen_side = true,
+                _ => return Err(FenError::InvalidCastling),
+            }
+        }
+    }
+    Ok(rights)
+}
+
+fn find_king(board: &[[Option<Piece>; 8]; 8], color: Color) -> Result<Pos, FenError> {
+    let king = Some(Piece::new(color, Kind::King));
+    let mut kings = (0..8)
+        .flat_map(|y| (0..8).map(move |x| Pos::new(x, y)))
+        .filter(|pos| board[pos.y() as usize][pos.x() as usize] == king);
+
+    match (kings.next(), kings.next()) {
+        (Some(pos), None) => Ok(pos),
+        _ => Err(FenError::InvalidKings(color)),
+    }
+}
+
+fn parse_piece(c: char) -> Option<Piece> {
+    let color = if c.is_ascii_uppercase() {
+        Color::White
+    } else {
+        Color::Black
+    };
+    Kind::from_letter(c.to_ascii_uppercase()).map(|kind| Piece::new(color, kind))
+}
+
+fn piece_char(piece: Piece) -> char {
+    match piece.color() {
+        Color::White => piece.kind().letter(),
+        Color::Black => piece.kind().letter().to_ascii_lowercase(),
+    }
+}
@@ -0,0 +1,102 @@
+//! A game of chess as it is played, keeping the moves that led to the current position. Unlike a
+//! single position, a game knows when the same position keeps coming back.
+
+use crate::chess::{Chess, DrawReason, Move, Outcome, Undo};
+
+/// A position together with the moves played to reach it, which can be taken back.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Game {
+    chess: Chess,
+    /// What is needed to take back every move played, oldest first.
+    history: Vec<Undo>,
+    /// The hash of the position before every move played, oldest first.
+    hashes: Vec<u64>,
+}
+
+impl Game {
+    /// Starts a game from the starting position.
+    pub fn new() -> Self {
+        Game::from(Chess::new())
+    }
+
+    /// Returns the current position.
+    pub fn chess(&self) -> &Chess {
+        &self.chess
+    }
+
+    /// Returns the moves played so far, oldest first.
+    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
+        self.history.iter().map(Undo::m)
+    }
+
+    /// Returns the hashes of the positions before every move played, oldest first.
+    pub fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    /// Plays a move, which must be legal in the current position.
+    pub fn perform(&mut self, m: Move) {
+        self.hashes.push(self.chess.zobrist());
+        self.history.push(self.chess.make_move(m));
+    }
+
+    /// Takes back the last move played, returning it, or `None` if no moves were played.
+    pub fn undo(&mut self) -> Option<Move> {
+        let undo = self.history.pop()?;
+        self.hashes.pop();
+        self.chess.unmake_move(undo);
+        Some(undo.m())
+    }
+
+    /// Counts how often the current position has occurred, including now.
+    pub fn repetitions(&self) -> usize {
+        earlier_occurrences(&self.hashes, &self.chess) + 1
+    }
+
+    /// Returns the outcome of the game like [`Chess::outcome`], but also ends the game in a draw
+    /// when a position repeats three times or no captures or pawn moves were made for fifty moves.
+    /// Checkmate on the last move takes precedence.
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.chess.outcome().or_else(|| {
+            let repetitions = self.repetitions();
+            let clock = self.chess.halfmove_clock;
+            let reason = if repetitions >= 5 {
+                DrawReason::FivefoldRepetition
+            } else if clock >= 150 {
+                DrawReason::SeventyFiveMoves
+            } else if repetitions >= 3 {
+                DrawReason::ThreefoldRepetition
+            } else if clock >= 100 {
+                DrawReason::FiftyMoves
+            } else {
+                return None;
+            };
+            Some(Outcome::Draw(reason))
+        })
+    }
+}
+
+impl From<Chess> for Game {
+    /// Starts a game from the given position, without knowing the moves that led to it.
+    fn from(chess: Chess) -> Self {
+        Game {
+            chess,
+            history: Vec::new(),
+            hashes: Vec::new(),
+        }
+    }
+}
+
+/// Counts how often `chess` occurred among the positions with the given hashes, which are the
+/// ones leading up to it, oldest first. Only positions since the last capture or pawn move can be
+/// the same, and only every other one has the same player to move.
+pub(crate) fn earlier_occurrences(hashes: &[u64], chess: &Chess) -> usize {
+    hashes
+        .iter()
+        .rev()
+        .take(chess.halfmove_clock as usize)
+        .skip(1)
+        .step_by(2)
+        .filter(|&&hash| hash == chess.zobrist())
+        .count()
+}
@@ -0,0 +1,82 @@
+//! Judging how safe the kings are from attack. In the middlegame, a king with pawns in front of
+//! it and no open columns beside it can withstand the pieces coming for it, while an exposed king
+//! is soon mated. Once most pieces are traded, there is little left to attack with, so these terms
+//! only count in the middlegame.
+
+use crate::bitboard::{self, adjacent_files, file, row, Bitboard};
+use crate::chess::{Chess, Color, Kind, Piece};
+use crate::evaluation::Tapered;
+use crate::pawns::forward;
+
+/// Returns how safe both kings are, positive if white's king is safer.
+pub fn king_safety(chess: &Chess) -> Tapered {
+    safety(chess, Color::White) - safety(chess, Color::Black)
+}
+
+/// Returns how safe the king of the given player is, positive if it is safe.
+fn safety(chess: &Chess, color: Color) -> Tapered {
+    let king = chess.kings[color.king_index()];
+    let own_pawns = chess.bitboard(Piece::new(color, Kind::Pawn));
+    let enemy_pawns = chess.bitboard(Piece::new(!color, Kind::Pawn));
+    let files = file(king.x()) | adjacent_files(king.x());
+
+    let mut score = Tapered::default();
+
+    // Pawns right in front of the king shield it best, and those a row further still help
+    let close = king + forward(color);
+    let far = close + forward(color);
+    score += SHIELD_CLOSE * (own_pawns & files & row(close.y())).len() as i16;
+    score += SHIELD_FAR * (own_pawns & files & row(far.y())).len() as i16;
+
+    // Rooks and queens attack down columns without pawns of their own player
+    for x in (king.x() - 1).max(0)..=(king.x() + 1).min(7) {
+        let file = file(x);
+        if (own_pawns & file).is_empty() {
+            score += if (enemy_pawns & file).is_empty() {
+                OPEN_FILE
+            } else {
+                HALF_OPEN_FILE
+            };
+        }
+    }
+
+    // Pieces attacking the tiles around the king, weighed by how dangerous they are. A single
+    // attacker is rarely a threat, but several together are, so the penalty grows quadratically.
+    let zone = bitboard::king_attacks(king) | Bitboard::from(king);
+    let occupied = chess.occupied();
+    let units: i32 = ATTACK_WEIGHTS
+        .iter()
+        .map(|&(kind, weight)| {
+            chess
+                .bitboard(Piece::new(!color, kind))
+                .into_iter()
+                .map(|from| {
+                    weight * (bitboard::attacks(kind, !color, from, occupied) & zone).len() as i32
+                })
+                .sum::<i32>()
+        })
+        .sum();
+    score += Tapered::new(-(units * units / 8).min(MAX_ATTACK_PENALTY) as i16, 0);
+
+    score
+}
+
+/// The bonus for every pawn of the king's player right in front of it or diagonally so.
+const SHIELD_CLOSE: Tapered = Tapered::new(12, 0);
+/// The bonus for every pawn of the king's player two rows in front of the king.
+const SHIELD_FAR: Tapered = Tapered::new(6, 0);
+/// The penalty for a column next to the king, or the king's own, without any pawns.
+const OPEN_FILE: Tapered = Tapered::new(-25, 0);
+/// The penalty for a column next to the king, or the king's own, with only enemy pawns.
+const HALF_OPEN_FILE: Tapered = Tapered::new(-15, 0);
+
+/// How dangerous every kind of piece is when it attacks the tiles around the king, per tile.
+const ATTACK_WEIGHTS: [(Kind, i32); 4] = [
+    (Kind::Knight, 2),
+    (Kind::Bishop, 2),
+    (Kind::Rook, 3),
+    (Kind::Queen, 5),
+];
+
+/// The largest penalty for attacks on the tiles around the king, in centipawns.
+const MAX_ATTACK_PENALTY: i32 = 500;
@@ -1,3 +1,15 @@
+pub mod activity;
+pub mod bitboard;
 pub mod chess;
 pub mod computer;
+pub mod evaluation;
+pub mod fen;
+pub mod game;
+pub mod king_safety;
+pub mod pawns;
+pub mod perft;
 pub mod pos;
+pub mod san;
+pub mod transposition;
+pub mod uci;
+mod zobrist;
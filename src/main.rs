@@ -1,96 +1,21 @@
-use schaakmaat::chess::{Chess, Outcome, Color, Piece};
-use schaakmaat::computer;
-use schaakmaat::pos::Pos;
-
-const MATE_IN_THREE: Chess = Chess {
-    board: [
-        [
-            Some(Piece::WHITE_QUEEN),
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-            None,
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_PAWN),
-            None,
-            Some(Piece::BLACK_PAWN),
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_KNIGHT),
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::BLACK_KING),
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_KNIGHT),
-            None,
-            Some(Piece::WHITE_ROOK),
-            Some(Piece::BLACK_PAWN),
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_PAWN),
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_KING),
-            None,
-        ],
-    ],
-    turn: Color::White,
-    kings: [Pos::new(6, 7), Pos::new(7, 4)],
-};
+use std::io::{self, IsTerminal};
+use std::sync::atomic::AtomicBool;
+
+use schaakmaat::chess::Outcome;
+use schaakmaat::computer::{self, SearchLimits};
+use schaakmaat::game::Game;
+use schaakmaat::pawns::PawnTable;
+use schaakmaat::transposition::TranspositionTable;
+use schaakmaat::uci;
 
 fn main() {
-    // let mut state = MATE_IN_THREE;
+    // GUIs talk to the engine through a pipe, and people can ask for UCI mode explicitly
+    if std::env::args().nth(1).as_deref() == Some("uci") || !io::stdin().is_terminal() {
+        uci::run(io::stdin().lock(), io::stdout());
+        return;
+    }
+
+    // let mut state = Chess::from_fen("Q7/5p2/5P1p/5PPN/6Pk/4N1Rp/7P/6K1 w - - 0 1").unwrap();
 
     // println!("{state}");
 
@@ -119,17 +44,43 @@ fn main() {
     // println!("move: {:?} best score: {:?}", best_move, state.evaluate());
     // println!("{state}");
 
-    let mut state = Chess::new();
+    let mut game = Game::new();
+    let mut table = TranspositionTable::default();
+    let mut pawns = PawnTable::default();
+    let limits = SearchLimits {
+        depth: Some(1),
+        ..SearchLimits::default()
+    };
+    let stop = AtomicBool::new(false);
 
-    println!("{state}");
+    println!("{}", game.chess());
 
-    while let Some(m) = computer::minimax(&state, 1).m {
-        state.perform(m);
-        println!("{state}");
-    }
+    // Without the game's history, the players could shuffle their pieces back and forth forever
+    let outcome = loop {
+        if let Some(outcome) = game.outcome() {
+            break outcome;
+        }
+
+        let result =
+            computer::iterative_deepening(&game, limits, &mut table, &mut pawns, &stop, |_| {});
+        let m = result.best_move().expect("no move in an unfinished game");
+
+        let san = game.chess().move_to_san(m);
+        let pv: Vec<String> = result.pv.iter().map(|m| m.to_uci()).collect();
+        println!("{san} ({} pv {})", result.score, pv.join(" "));
+
+        game.perform(m);
+        println!("{}", game.chess());
+    };
 
-    match state.outcome().unwrap() {
+    match outcome {
         Outcome::Winner(color) => println!("{color} wins!"),
         Outcome::Stalemate => println!("it's a stalemate!"),
+        Outcome::Draw(reason) => println!("it's a draw by {reason}!"),
     }
+    println!(
+        "transposition table: {} hits, {} misses",
+        table.hits(),
+        table.misses()
+    );
 }
@@ -0,0 +1,173 @@
+//! Judging the pawn structure: pawns that cannot protect each other are weak, and pawns that no
+//! enemy pawn can stop may promote. Pawns move rarely and never back, so the same structure comes
+//! up in many positions of a search, and its evaluation is remembered in a table keyed by the hash
+//! of only the pawns.
+
+use crate::bitboard::{self, adjacent_files, file, Bitboard};
+use crate::chess::{Chess, Color, Kind, Piece};
+use crate::evaluation::Tapered;
+use crate::pos::{Pos, Shift};
+use crate::transposition;
+
+/// What the pawns alone say about a position.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct PawnStructure {
+    /// The penalties for doubled, isolated and backward pawns and the bonuses for passed pawns,
+    /// positive for white and negative for black.
+    pub score: Tapered,
+    /// The passed pawns of both players, whose chances also depend on the pieces in their way.
+    pub passed: Bitboard,
+}
+
+impl PawnStructure {
+    /// Evaluates the pawns of both players.
+    pub fn new(chess: &Chess) -> Self {
+        let mut structure = PawnStructure::default();
+        for color in [Color::White, Color::Black] {
+            let own = chess.bitboard(Piece::new(color, Kind::Pawn));
+            let enemy = chess.bitboard(Piece::new(!color, Kind::Pawn));
+
+            let mut score = Tapered::default();
+            for pos in own {
+                let file = file(pos.x());
+                let adjacent = adjacent_files(pos.x());
+                let ahead = ahead(color, pos.y());
+
+                // Every pawn but the frontmost on a file is counted, since the pawns ahead of it
+                // stand in its way
+                let doubled = !(own & file & ahead).is_empty();
+                if doubled {
+                    score += DOUBLED;
+                }
+
+                if (own & adjacent).is_empty() {
+                    score += ISOLATED;
+                } else if (own & adjacent & !ahead).is_empty()
+                    && !(bitboard::pawn_attacks(color, pos + forward(color)) & enemy).is_empty()
+                {
+                    // No pawn beside or behind it can come to its aid, and it cannot advance
+                    // without being taken
+                    score += BACKWARD;
+                }
+
+                if !doubled && (enemy & (file | adjacent) & ahead).is_empty() {
+                    structure.passed |= Bitboard::from(pos);
+                    score += PASSED[relative_row(color, pos)];
+                }
+            }
+
+            structure.score += match color {
+                Color::Black => -score,
+                Color::White => score,
+            };
+        }
+        structure
+    }
+
+    /// Returns the penalty for the passed pawns with a piece in their way to promotion, which
+    /// have to wait for it to leave or be captured. Unlike the rest of the structure, this
+    /// depends on the other pieces on the board.
+    pub fn blocked(&self, chess: &Chess) -> Tapered {
+        let occupied = chess.occupied();
+        self.passed
+            .into_iter()
+            .filter_map(|pos| {
+                let color = chess[pos]?.color();
+                if (file(pos.x()) & ahead(color, pos.y()) & occupied).is_empty() {
+                    return None;
+                }
+                // Half of the bonus is lost
+                let bonus = PASSED[relative_row(color, pos)];
+                let penalty = Tapered::new(bonus.middlegame / 2, bonus.endgame / 2);
+                Some(match color {
+                    Color::Black => penalty,
+                    Color::White => -penalty,
+                })
+            })
+            .sum()
+    }
+}
+
+/// Remembers the structure of the pawns in positions seen before, keyed by [`pawn_zobrist`].
+/// Like the [`TranspositionTable`], it has a fixed number of slots, each holding the structure
+/// last stored in it.
+///
+/// [`pawn_zobrist`]: crate::chess::Chess::pawn_zobrist
+/// [`TranspositionTable`]: crate::transposition::TranspositionTable
+#[derive(Clone, Debug)]
+pub struct PawnTable {
+    entries: Vec<Option<(u64, PawnStructure)>>,
+}
+
+impl PawnTable {
+    /// Creates a table of the given number of megabytes, like [`TranspositionTable::new`].
+    ///
+    /// [`TranspositionTable::new`]: crate::transposition::TranspositionTable::new
+    pub fn new(megabytes: usize) -> Self {
+        PawnTable {
+            entries: vec![None; transposition::slots::<Option<(u64, PawnStructure)>>(megabytes)],
+        }
+    }
+
+    /// Returns the structure of the pawns in the given position, evaluating and storing it if it
+    /// is not in the table yet.
+    pub fn get(&mut self, chess: &Chess) -> PawnStructure {
+        let zobrist = chess.pawn_zobrist();
+        let slot = (zobrist % self.entries.len() as u64) as usize;
+        match self.entries[slot] {
+            Some((stored, structure)) if stored == zobrist => structure,
+            _ => {
+                let structure = PawnStructure::new(chess);
+                self.entries[slot] = Some((zobrist, structure));
+                structure
+            }
+        }
+    }
+}
+
+impl Default for PawnTable {
+    fn default() -> Self {
+        PawnTable::new(1)
+    }
+}
+
+const DOUBLED: Tapered = Tapered::new(-10, -20);
+const ISOLATED: Tapered = Tapered::new(-10, -15);
+const BACKWARD: Tapered = Tapered::new(-8, -10);
+
+/// The bonus for a passed pawn, indexed by how many rows it has advanced from its player's side
+/// of the board.
+const PASSED: [Tapered; 8] = [
+    Tapered::new(0, 0),
+    Tapered::new(5, 10),
+    Tapered::new(5, 15),
+    Tapered::new(10, 25),
+    Tapered::new(20, 45),
+    Tapered::new(35, 75),
+    Tapered::new(60, 120),
+    Tapered::new(0, 0),
+];
+
+/// Returns the rows a pawn of the given color on row `y` still has to pass to promote.
+pub(crate) fn ahead(color: Color, y: isize) -> Bitboard {
+    match color {
+        Color::Black => Bitboard(u64::MAX.checked_shl((y as u32 + 1) * 8).unwrap_or(0)),
+        Color::White => Bitboard(!(u64::MAX << (y * 8))),
+    }
+}
+
+/// Returns the direction the pawns of the given color move in.
+pub(crate) fn forward(color: Color) -> Shift {
+    match color {
+        Color::Black => Shift::DOWN,
+        Color::White => Shift::UP,
+    }
+}
+
+/// Returns how many rows a piece of the given color on the given tile is from its own side.
+pub(crate) fn relative_row(color: Color, pos: Pos) -> usize {
+    match color {
+        Color::Black => pos.y() as usize,
+        Color::White => 7 - pos.y() as usize,
+    }
+}
@@ -0,0 +1,55 @@
+//! Counting the positions reachable in a number of moves, to check the move generator against
+//! the published counts of well-known positions.
+
+use crate::chess::{Chess, Move, MoveList};
+
+impl Chess {
+    /// Counts the sequences of legal moves of the given length, i.e. the leaves of the game tree
+    /// that many moves deep. A single mistake in the move generator, like a missing en passant
+    /// capture or castling through check, changes the count.
+    pub fn perft(&self, depth: u8) -> u64 {
+        let mut chess = *self;
+        chess.count_leaves(depth)
+    }
+
+    /// Counts the sequences of legal moves like [`perft`], broken down by their first move. When a
+    /// count is off, comparing this to another engine's breakdown narrows down the position where
+    /// moves go missing.
+    ///
+    /// [`perft`]: #method.perft
+    pub fn divide(&self, depth: u8) -> Vec<(Move, u64)> {
+        self.moves()
+            .map(|m| {
+                let mut copy = *self;
+                copy.perform(m);
+                (m, copy.count_leaves(depth.saturating_sub(1)))
+            })
+            .collect()
+    }
+
+    /// Counts like [`perft`], making and taking back the moves in place rather than copying the
+    /// position for every move.
+    ///
+    /// [`perft`]: #method.perft
+    fn count_leaves(&mut self, depth: u8) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut moves = MoveList::new();
+        self.generate_into(&mut moves);
+        match depth {
+            // The positions after the last move do not need to be played out to be counted
+            1 => moves.len() as u64,
+            _ => moves
+                .iter()
+                .map(|&m| {
+                    let undo = self.make_move(m);
+                    let count = self.count_leaves(depth - 1);
+                    self.unmake_move(undo);
+                    count
+                })
+                .sum(),
+        }
+    }
+}
@@ -3,27 +3,49 @@ use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Pos {
-    x: isize,
-    y: isize,
+    // Stored as small numbers to keep moves, and lists of them, compact
+    x: i8,
+    y: i8,
 }
 
 impl Pos {
     pub const fn new(x: isize, y: isize) -> Self {
-        Pos { x, y }
+        Pos {
+            x: x as i8,
+            y: y as i8,
+        }
     }
 
     pub fn x(&self) -> isize {
-        self.x
+        self.x as isize
     }
 
     pub fn y(&self) -> isize {
-        self.y
+        self.y as isize
+    }
+
+    /// Parses a tile name like `e4`, where the letter is the column (`x`) and the number counts
+    /// rows from white's side of the board, so `a8` is `(0, 0)` and `h1` is `(7, 7)`.
+    pub fn from_algebraic(name: &str) -> Option<Pos> {
+        match name.as_bytes() {
+            [file @ b'a'..=b'h', rank @ b'1'..=b'8'] => {
+                Some(Pos::new((file - b'a') as isize, (b'8' - rank) as isize))
+            }
+            _ => None,
+        }
+    }
+
+    /// Writes the tile name of this position, the inverse of [`from_algebraic`].
+    ///
+    /// [`from_algebraic`]: #method.from_algebraic
+    pub fn to_algebraic(&self) -> String {
+        self.to_string()
     }
 }
 
 impl Display for Pos {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}", (self.y as u8 + 65) as char, 8 - self.x)
+        write!(f, "{}{}", (self.x as u8 + b'a') as char, 8 - self.y)
     }
 }
 
@@ -32,8 +54,8 @@ impl Sub for Pos {
 
     fn sub(self, rhs: Self) -> Self::Output {
         Shift {
-            dx: self.x - rhs.x,
-            dy: self.y - rhs.y,
+            dx: self.x() - rhs.x(),
+            dy: self.y() - rhs.y(),
         }
     }
 }
@@ -42,17 +64,13 @@ impl Add<Shift> for Pos {
     type Output = Pos;
 
     fn add(self, rhs: Shift) -> Self::Output {
-        Pos {
-            x: self.x + rhs.dx,
-            y: self.y + rhs.dy,
-        }
+        Pos::new(self.x() + rhs.dx, self.y() + rhs.dy)
     }
 }
 
 impl AddAssign<Shift> for Pos {
     fn add_assign(&mut self, rhs: Shift) {
-        self.x = self.x + rhs.dx;
-        self.y = self.y + rhs.dy;
+        *self = *self + rhs;
     }
 }
 
@@ -60,17 +78,13 @@ impl Sub<Shift> for Pos {
     type Output = Pos;
 
     fn sub(self, rhs: Shift) -> Self::Output {
-        Pos {
-            x: self.x - rhs.dx,
-            y: self.y - rhs.dy,
-        }
+        Pos::new(self.x() - rhs.dx, self.y() - rhs.dy)
     }
 }
 
 impl SubAssign<Shift> for Pos {
     fn sub_assign(&mut self, rhs: Shift) {
-        self.x = self.x - rhs.dx;
-        self.y = self.y - rhs.dy;
+        *self = *self - rhs;
     }
 }
 
@@ -0,0 +1,182 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::chess::{Chess, Kind, Move, Outcome};
+use crate::pos::{Pos, Shift};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum SanError {
+    /// The text is not a move in Standard Algebraic Notation.
+    InvalidSyntax,
+    /// No legal move in the current position matches the text.
+    IllegalMove,
+    /// More than one legal move matches the text.
+    AmbiguousMove,
+}
+
+impl Display for SanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SanError::InvalidSyntax => write!(f, "invalid move syntax"),
+            SanError::IllegalMove => write!(f, "illegal move"),
+            SanError::AmbiguousMove => write!(f, "ambiguous move"),
+        }
+    }
+}
+
+impl Error for SanError {}
+
+impl Chess {
+    /// Writes a legal move of the current player in Standard Algebraic Notation, e.g. `Nbd7`,
+    /// `exd6`, `e8=Q+` or `O-O-O#`.
+    pub fn move_to_san(&self, m: Move) -> String {
+        let piece = self[m.from()].expect("no piece to move");
+        let dx = m.to().x() - m.from().x();
+
+        let mut san = if piece.kind() == Kind::King && dx.abs() == 2 {
+            if dx > 0 { "O-O" } else { "O-O-O" }.to_owned()
+        } else {
+            let capture = self.is_capture(m);
+
+            let mut san = String::new();
+            if piece.kind() == Kind::Pawn {
+                if capture {
+                    san.push_str(&m.from().to_algebraic()[..1]);
+                }
+            } else {
+                san.push(piece.kind().letter());
+                san.push_str(&self.disambiguation(m));
+            }
+            if capture {
+                san.push('x');
+            }
+            san.push_str(&m.to().to_algebraic());
+            if let Some(kind) = m.promotion() {
+                san.push('=');
+                san.push(kind.letter());
+            }
+            san
+        };
+
+        let mut after = *self;
+        after.perform(m);
+        if let Some(Outcome::Winner(_)) = after.outcome() {
+            san.push('#');
+        } else if after.is_checked(after.turn) {
+            san.push('+');
+        }
+
+        san
+    }
+
+    /// Returns the part of a move's origin that is needed to tell it apart from other moves of the
+    /// same kind of piece to the same tile: nothing, the file, the row, or both.
+    fn disambiguation(&self, m: Move) -> String {
+        let kind = self[m.from()].map(|piece| piece.kind());
+        let others: Vec<Pos> = self
+            .moves()
+            .filter(|other| other.to() == m.to() && other.from() != m.from())
+            .filter(|other| self[other.from()].map(|piece| piece.kind()) == kind)
+            .map(|other| other.from())
+            .collect();
+
+        let from = m.from().to_algebraic();
+        if others.is_empty() {
+            String::new()
+        } else if others.iter().all(|other| other.x() != m.from().x()) {
+            from[..1].to_owned()
+        } else if others.iter().all(|other| other.y() != m.from().y()) {
+            from[1..].to_owned()
+        } else {
+            from
+        }
+    }
+
+    /// Parses a move of the current player in Standard Algebraic Notation. Check and mate
+    /// suffixes and annotations like `!?` are accepted but not verified, and an unneeded
+    /// disambiguation like `Ngf3` is allowed.
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let san = san.trim().trim_end_matches(['+', '#', '!', '?']);
+
+        let king = self.kings[self.turn.king_index()];
+        let pattern = match san {
+            "O-O" | "0-0" => Pattern::castle(king + Shift::RIGHT * 2),
+            "O-O-O" | "0-0-0" => Pattern::castle(king + Shift::LEFT * 2),
+            _ => Pattern::parse(san).ok_or(SanError::InvalidSyntax)?,
+        };
+
+        let mut candidates = self.moves().filter(|m| {
+            self[m.from()].map(|piece| piece.kind()) == Some(pattern.kind)
+                && m.to() == pattern.to
+                && m.promotion() == pattern.promotion
+                && pattern.from_x.is_none_or(|x| m.from().x() == x)
+                && pattern.from_y.is_none_or(|y| m.from().y() == y)
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(m), None) => Ok(m),
+            (None, _) => Err(SanError::IllegalMove),
+            (Some(_), Some(_)) => Err(SanError::AmbiguousMove),
+        }
+    }
+}
+
+/// The parts of a move that can be read from its notation, which the legal moves are matched
+/// against.
+struct Pattern {
+    kind: Kind,
+    from_x: Option<isize>,
+    from_y: Option<isize>,
+    to: Pos,
+    promotion: Option<Kind>,
+}
+
+impl Pattern {
+    fn castle(to: Pos) -> Self {
+        Pattern {
+            kind: Kind::King,
+            from_x: None,
+            from_y: None,
+            to,
+            promotion: None,
+        }
+    }
+
+    /// Parses a move that is not a castle, e.g. `e4`, `exd5`, `Nbd7`, `R1xa3` or `e8=Q`.
+    fn parse(san: &str) -> Option<Self> {
+        let (san, promotion) = match san.split_once('=') {
+            None => (san, None),
+            Some((san, promotion)) => match promotion.chars().collect::<Vec<_>>()[..] {
+                [letter @ ('Q' | 'R' | 'B' | 'N')] => (san, Kind::from_letter(letter)),
+                _ => return None,
+            },
+        };
+
+        let (kind, san) = match san.chars().next()? {
+            letter @ ('K' | 'Q' | 'R' | 'B' | 'N') => (Kind::from_letter(letter)?, &san[1..]),
+            _ => (Kind::Pawn, san),
+        };
+
+        let to = Pos::from_algebraic(san.get(san.len().checked_sub(2)?..)?)?;
+
+        let mut from_x = None;
+        let mut from_y = None;
+        for c in san[..san.len() - 2].trim_end_matches('x').chars() {
+            match c {
+                'a'..='h' if from_x.is_none() && from_y.is_none() => {
+                    from_x = Some(c as isize - 'a' as isize)
+                }
+                '1'..='8' if from_y.is_none() => from_y = Some('8' as isize - c as isize),
+                _ => return None,
+            }
+        }
+
+        Some(Pattern {
+            kind,
+            from_x,
+            from_y,
+            to,
+            promotion,
+        })
+    }
+}
@@ -0,0 +1,106 @@
+use std::mem::size_of;
+
+use crate::chess::Move;
+use crate::computer::Score;
+
+/// Remembers what earlier searches found out about positions, keyed by their Zobrist hash, so
+/// that a position reached through a different order of moves does not have to be searched again.
+///
+/// The table has a fixed number of slots. Every position maps to a single slot, and a new result
+/// simply replaces whatever was stored there before.
+#[derive(Clone, Debug)]
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    hits: u64,
+    misses: u64,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    /// The full hash of the position, to tell it apart from other positions sharing the slot.
+    pub zobrist: u64,
+    /// How many moves deep the position was searched.
+    pub depth: u8,
+    /// The score of the position, with mates counted from the position itself.
+    pub score: Score,
+    pub bound: Bound,
+    pub best_move: Option<Move>,
+}
+
+/// How a stored score relates to the actual score of the position. A search that skips moves
+/// only finds out that a position is at least or at most worth a certain score.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Bound {
+    /// The score is exact.
+    Exact,
+    /// The actual score is at least the stored score.
+    Lower,
+    /// The actual score is at most the stored score.
+    Upper,
+}
+
+impl TranspositionTable {
+    /// Creates a table using roughly the given number of megabytes of memory, with at least one
+    /// slot.
+    pub fn new(megabytes: usize) -> Self {
+        TranspositionTable {
+            entries: vec![None; slots::<Option<Entry>>(megabytes)],
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Looks up the entry for the position with the given hash, counting it as a hit or a miss.
+    pub fn get(&mut self, zobrist: u64) -> Option<Entry> {
+        let entry = self.entries[self.slot(zobrist)].filter(|entry| entry.zobrist == zobrist);
+        match entry {
+            Some(_) => self.hits += 1,
+            None => self.misses += 1,
+        }
+        entry
+    }
+
+    /// Stores an entry, replacing whatever was in its slot.
+    pub fn insert(&mut self, entry: Entry) {
+        let slot = self.slot(entry.zobrist);
+        self.entries[slot] = Some(entry);
+    }
+
+    /// Forgets all entries and resets the hit and miss counts, e.g. before a new game.
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    /// The number of lookups that found an entry for their position since the last clear.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// The number of lookups that did not find an entry for their position since the last clear.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// The number of entries the table can hold.
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn slot(&self, zobrist: u64) -> usize {
+        (zobrist % self.entries.len() as u64) as usize
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        TranspositionTable::new(16)
+    }
+}
+
+/// Returns how many slots holding a `T` fit in roughly the given number of megabytes of memory,
+/// and at least one.
+pub(crate) fn slots<T>(megabytes: usize) -> usize {
+    (megabytes.saturating_mul(1024 * 1024) / size_of::<T>()).max(1)
+}
@@ -0,0 +1,269 @@
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::chess::{Chess, Color, Move};
+use crate::computer::{self, SearchLimits};
+use crate::game::Game;
+use crate::pawns::PawnTable;
+use crate::transposition::TranspositionTable;
+
+/// The largest transposition table in megabytes that can be asked for with the `Hash` option.
+const MAX_HASH: usize = 4096;
+
+/// Runs the engine with the Universal Chess Interface, reading commands from `input` and writing
+/// responses to `output` until `quit` is received or the input ends.
+pub fn run<R, W>(input: R, output: W)
+where
+    R: BufRead,
+    W: Write + Send + 'static,
+{
+    let mut uci = Uci::new(output);
+    for line in input.lines() {
+        match line {
+            Ok(line) if uci.handle(&line) => {}
+            _ => break,
+        }
+    }
+    uci.stop();
+}
+
+struct Uci<W> {
+    /// Shared with the search thread, which reports its progress and best move through it.
+    output: Arc<Mutex<W>>,
+    /// The position to search, with the moves leading to it so that repetitions are avoided.
+    position: Game,
+    /// Shared with the search thread, which holds on to it while searching.
+    table: Arc<Mutex<TranspositionTable>>,
+    /// Shared with the search thread like `table`.
+    pawns: Arc<Mutex<PawnTable>>,
+    search: Option<Search>,
+}
+
+/// A search running in the background, which can be told to stop.
+struct Search {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl<W> Uci<W>
+where
+    W: Write + Send + 'static,
+{
+    fn new(output: W) -> Self {
+        Uci {
+            output: Arc::new(Mutex::new(output)),
+            position: Game::new(),
+            table: Arc::default(),
+            pawns: Arc::default(),
+            search: None,
+        }
+    }
+
+    /// Handles a single command, returning `false` if the engine should quit. Unknown commands
+    /// are ignored, as the protocol requires.
+    fn handle(&mut self, line: &str) -> bool {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["uci", ..] => {
+                self.send("id name Schaakmaat");
+                self.send("id author Sandra ter Maat");
+                self.send(&format!(
+                    "option name Hash type spin default 16 min 1 max {MAX_HASH}"
+                ));
+                self.send("uciok");
+            }
+            ["isready", ..] => self.send("readyok"),
+            ["ucinewgame", ..] => {
+                self.stop();
+                self.position = Game::new();
+                self.table.lock().unwrap().clear();
+            }
+            ["setoption", "name", "Hash", "value", megabytes] => {
+                self.stop();
+                if let Ok(megabytes) = megabytes.parse::<usize>() {
+                    let megabytes = megabytes.clamp(1, MAX_HASH);
+                    *self.table.lock().unwrap() = TranspositionTable::new(megabytes);
+                }
+            }
+            ["position", args @ ..] => {
+                self.stop();
+                match parse_position(args) {
+                    Ok(position) => self.position = position,
+                    Err(error) => self.send(&format!("info string {error}")),
+                }
+            }
+            ["go", "perft", depth, ..] => {
+                self.stop();
+                if let Ok(depth) = depth.parse() {
+                    self.divide(depth);
+                }
+            }
+            ["go", args @ ..] => {
+                // The best move of an infinite search is only wanted once it is stopped, even if
+                // the search ends before that
+                let infinite = args.iter().any(|arg| matches!(*arg, "infinite" | "ponder"));
+                self.go(parse_limits(args, self.position.chess().turn), infinite);
+            }
+            ["stop", ..] => self.stop(),
+            ["quit", ..] => return false,
+            _ => {}
+        }
+        true
+    }
+
+    fn send(&self, line: &str) {
+        send(&self.output, line);
+    }
+
+    /// Starts searching the current position in the background. With `infinite`, the best move is
+    /// held back until the search is stopped.
+    fn go(&mut self, limits: SearchLimits, infinite: bool) {
+        self.stop();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let stop = stop.clone();
+            let output = self.output.clone();
+            let table = self.table.clone();
+            let pawns = self.pawns.clone();
+            let position = self.position.clone();
+            thread::spawn(move || {
+                let mut table = table.lock().unwrap();
+                let mut pawns = pawns.lock().unwrap();
+                let best_move = search(position, limits, &mut table, &mut pawns, &stop, &output);
+                while infinite && !stop.load(Ordering::Relaxed) {
+                    thread::park();
+                }
+                send(&output, &format!("bestmove {best_move}"));
+            })
+        };
+
+        self.search = Some(Search { stop, handle });
+    }
+
+    /// Counts the positions reachable from the current position in the given number of moves,
+    /// broken down by the first move, as a check on the move generator.
+    fn divide(&self, depth: u8) {
+        let divide = self.position.chess().divide(depth);
+        for (m, nodes) in &divide {
+            self.send(&format!("{}: {nodes}", m.to_uci()));
+        }
+        let nodes: u64 = divide.iter().map(|(_, nodes)| nodes).sum();
+        self.send("");
+        self.send(&format!("Nodes searched: {nodes}"));
+    }
+
+    /// Stops the running search, if any, and waits for it to report its best move.
+    fn stop(&mut self) {
+        if let Some(search) = self.search.take() {
+            search.stop.store(true, Ordering::Relaxed);
+            search.handle.thread().unpark();
+            search.handle.join().expect("search thread panicked");
+        }
+    }
+}
+
+fn send<W: Write>(output: &Mutex<W>, line: &str) {
+    let mut output = output.lock().unwrap();
+    // There is nobody left to tell if the GUI has gone away, so errors are ignored
+    let _ = writeln!(output, "{line}");
+    let _ = output.flush();
+}
+
+/// Parses the arguments of `position`: `startpos` or `fen` followed by the six FEN fields,
+/// optionally followed by `moves` and a list of moves in UCI notation.
+fn parse_position(args: &[&str]) -> Result<Game, String> {
+    let (position, moves) = match args {
+        ["startpos", moves @ ..] => (Chess::new(), moves),
+        ["fen", rest @ ..] => {
+            let end = rest
+                .iter()
+                .position(|arg| *arg == "moves")
+                .unwrap_or(rest.len());
+            let position = Chess::from_fen(&rest[..end].join(" ")).map_err(|e| e.to_string())?;
+            (position, &rest[end..])
+        }
+        _ => return Err("expected 'startpos' or 'fen'".to_owned()),
+    };
+
+    let mut position = Game::from(position);
+    if let ["moves", moves @ ..] = moves {
+        for uci in moves {
+            let m = Move::from_uci(uci)
+                .filter(|m| position.chess().moves().any(|legal| legal == *m))
+                .ok_or_else(|| format!("illegal move '{uci}'"))?;
+            position.perform(m);
+        }
+    }
+
+    Ok(position)
+}
+
+/// Parses the arguments of `go` into the limits for the player to move. Without any limits, the
+/// search runs until it is stopped.
+///
+/// A limit that is given but cannot be used, such as the negative time left after the clock ran
+/// out, is treated as the smallest limit possible, so that the engine still moves right away
+/// rather than searching forever.
+fn parse_limits(args: &[&str], turn: Color) -> SearchLimits {
+    let mut limits = SearchLimits::default();
+
+    let millis = |value: &str| {
+        let millis: i64 = value.parse().unwrap_or(0);
+        Duration::from_millis(millis.max(0) as u64)
+    };
+
+    for pair in args.windows(2) {
+        match (pair[0], turn) {
+            ("depth", _) => limits.depth = Some(pair[1].parse().unwrap_or(1)),
+            ("nodes", _) => limits.nodes = Some(pair[1].parse().unwrap_or(1)),
+            ("movetime", _) => limits.move_time = Some(millis(pair[1])),
+            ("wtime", Color::White) | ("btime", Color::Black) => {
+                limits.clock = Some(millis(pair[1]))
+            }
+            ("winc", Color::White) | ("binc", Color::Black) => limits.increment = millis(pair[1]),
+            _ => {}
+        }
+    }
+
+    limits
+}
+
+/// Searches ever deeper until a limit is reached or the search is stopped, reporting every
+/// completed depth, and returns the best move found in UCI notation.
+fn search<W: Write>(
+    position: Game,
+    limits: SearchLimits,
+    table: &mut TranspositionTable,
+    pawns: &mut PawnTable,
+    stop: &AtomicBool,
+    output: &Mutex<W>,
+) -> String {
+    let result = computer::iterative_deepening(&position, limits, table, pawns, stop, |result| {
+        if result.pv.is_empty() {
+            return;
+        }
+        // The protocol gives scores from the engine's point of view rather than white's
+        let score = match position.chess().turn {
+            Color::Black => -result.score,
+            Color::White => result.score,
+        };
+        let time = result.time.as_millis();
+        let nps = result.nodes as u128 * 1000 / time.max(1);
+        let pv: Vec<String> = result.pv.iter().map(|m| m.to_uci()).collect();
+        send(
+            output,
+            &format!(
+                "info depth {} score {score} nodes {} nps {nps} time {time} pv {}",
+                result.depth,
+                result.nodes,
+                pv.join(" ")
+            ),
+        );
+    });
+
+    result.best_move().map_or("0000".to_owned(), |m| m.to_uci())
+}
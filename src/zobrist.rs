@@ -0,0 +1,75 @@
+//! Random keys for Zobrist hashing. A position's hash is the XOR of the keys of everything in it,
+//! so moving a piece only needs the keys of what changed, rather than hashing the whole board.
+
+use crate::chess::{Castling, Color, Piece};
+use crate::pos::Pos;
+
+/// A key for every kind of piece of either color on every tile, indexed by
+/// `[color][kind][y * 8 + x]`.
+const PIECES: [[[u64; 64]; 6]; 2] = {
+    let mut keys = [[[0; 64]; 6]; 2];
+    let mut seed = 0x5343_4841_414b_4d41;
+    let mut i = 0;
+    while i < 2 * 6 * 64 {
+        let key;
+        (key, seed) = split_mix(seed);
+        keys[i / (6 * 64)][i / 64 % 6][i % 64] = key;
+        i += 1;
+    }
+    keys
+};
+
+/// Toggled whenever the turn passes, so the same board with a different player to move differs.
+pub const TURN: u64 = split_mix(0x7475_726e).0;
+
+/// A key for every combination of castling rights, indexed by the rights as four bits.
+const CASTLING: [u64; 16] = {
+    let mut keys = [0; 16];
+    let mut seed = 0x6361_7374_6c65;
+    let mut i = 1;
+    while i < 16 {
+        (keys[i], seed) = split_mix(seed);
+        i += 1;
+    }
+    keys
+};
+
+/// A key for the column of a tile that can be captured onto en passant.
+const EN_PASSANT: [u64; 8] = {
+    let mut keys = [0; 8];
+    let mut seed = 0x656e_2070_6173_7361;
+    let mut i = 0;
+    while i < 8 {
+        (keys[i], seed) = split_mix(seed);
+        i += 1;
+    }
+    keys
+};
+
+/// The SplitMix64 generator, returning a pseudo-random number and the next seed. It is used
+/// rather than a random crate so that the keys, and therefore all hashes, are the same on every
+/// run.
+const fn split_mix(seed: u64) -> (u64, u64) {
+    let seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (z ^ (z >> 31), seed)
+}
+
+pub fn piece(piece: Piece, pos: Pos) -> u64 {
+    PIECES[piece.color().king_index()][piece.kind() as usize][(pos.y() * 8 + pos.x()) as usize]
+}
+
+pub fn castling(rights: [Castling; 2]) -> u64 {
+    let [white, black] = [Color::White, Color::Black].map(|color| rights[color.king_index()]);
+    let index = white.king_side as usize
+        | (white.queen_side as usize) << 1
+        | (black.king_side as usize) << 2
+        | (black.queen_side as usize) << 3;
+    CASTLING[index]
+}
+
+pub fn en_passant(pos: Pos) -> u64 {
+    EN_PASSANT[pos.x() as usize]
+}
@@ -0,0 +1,78 @@
+use schaakmaat::activity;
+use schaakmaat::chess::Chess;
+use schaakmaat::evaluation::Tapered;
+
+fn state(fen: &str) -> Chess {
+    Chess::from_fen(fen).unwrap()
+}
+
+#[test]
+fn balanced_at_start() {
+    assert_eq!(activity::activity(&Chess::new()), Tapered::default());
+}
+
+#[test]
+fn mobility() {
+    // The bishop in the corner behind its own pawn reaches nothing, unlike one in the center
+    let cornered = state("4k3/8/8/8/8/8/1P6/B3K3 w - - 0 1");
+    let central = state("4k3/8/8/8/3B4/8/1P6/4K3 w - - 0 1");
+    assert!(activity::mobility(&cornered).middlegame < 0);
+    assert!(activity::mobility(&central).middlegame > 0);
+
+    // Tiles attacked by enemy pawns do not count
+    let covered = state("4k3/8/3p4/8/3B4/8/1P6/4K3 w - - 0 1");
+    assert!(activity::mobility(&covered).middlegame < activity::mobility(&central).middlegame);
+}
+
+#[test]
+fn rooks_on_open_files() {
+    let open = state("4k3/pp6/8/8/8/8/PP6/3RK3 w - - 0 1");
+    let half_open = state("4k3/pp1p4/8/8/8/8/PP6/3RK3 w - - 0 1");
+    let closed = state("4k3/pp6/8/8/8/8/PP1P4/3RK3 w - - 0 1");
+    assert_eq!(activity::rooks_on_open_files(&open), Tapered::new(25, 10));
+    assert_eq!(
+        activity::rooks_on_open_files(&half_open),
+        Tapered::new(12, 6)
+    );
+    assert_eq!(activity::rooks_on_open_files(&closed), Tapered::default());
+}
+
+#[test]
+fn bishop_pair() {
+    let pair = state("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1");
+    assert_eq!(activity::bishop_pair(&pair), Tapered::new(30, 50));
+
+    // Two bishops on tiles of the same color are no pair
+    let same_color = state("4k3/8/8/8/8/8/8/3BKB2 w - - 0 1");
+    assert_eq!(activity::bishop_pair(&same_color), Tapered::default());
+}
+
+#[test]
+fn knight_outposts() {
+    let outpost = state("4k3/p7/8/3N4/4P3/8/8/4K3 w - - 0 1");
+    assert_eq!(activity::knight_outposts(&outpost), Tapered::new(20, 10));
+
+    // A black pawn on a neighboring column can still chase the knight away
+    let chased = state("4k3/2p5/8/3N4/4P3/8/8/4K3 w - - 0 1");
+    assert_eq!(activity::knight_outposts(&chased), Tapered::default());
+
+    // The fourth row is the closest to home that counts, and the sixth the farthest
+    let fourth = state("4k3/8/8/8/3N4/4P3/8/4K3 w - - 0 1");
+    assert_eq!(activity::knight_outposts(&fourth), Tapered::new(20, 10));
+    let third = state("4k3/8/8/8/8/3N4/4P3/4K3 w - - 0 1");
+    assert_eq!(activity::knight_outposts(&third), Tapered::default());
+    let sixth = state("k7/8/3N4/4P3/8/8/8/4K3 w - - 0 1");
+    assert_eq!(activity::knight_outposts(&sixth), Tapered::new(20, 10));
+    let seventh = state("4k3/3N4/4P3/8/8/8/8/4K3 w - - 0 1");
+    assert_eq!(activity::knight_outposts(&seventh), Tapered::default());
+}
+
+#[test]
+fn rooks_on_seventh() {
+    let seventh = state("6k1/R4ppp/8/8/8/8/8/6K1 w - - 0 1");
+    assert_eq!(activity::rooks_on_seventh(&seventh), Tapered::new(20, 30));
+
+    // Without pawns to attack or a king to confine, the row is not worth more than any other
+    let empty = state("8/R7/8/4k3/8/8/8/6K1 w - - 0 1");
+    assert_eq!(activity::rooks_on_seventh(&empty), Tapered::default());
+}
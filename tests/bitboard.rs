@@ -0,0 +1,84 @@
+use schaakmaat::bitboard::{self, Bitboard};
+use schaakmaat::chess::{Chess, Color, Kind, Piece};
+use schaakmaat::pos::Pos;
+
+fn tiles(names: &[&str]) -> Bitboard {
+    names
+        .iter()
+        .map(|name| Bitboard::from(Pos::from_algebraic(name).unwrap()))
+        .fold(Bitboard::EMPTY, |tiles, tile| tiles | tile)
+}
+
+fn tile(name: &str) -> Pos {
+    Pos::from_algebraic(name).unwrap()
+}
+
+#[test]
+fn tiles_in_order() {
+    let set = tiles(&["h1", "a8", "e4"]);
+    assert_eq!(set.len(), 3);
+    assert!(set.contains(tile("e4")));
+    assert!(!set.contains(tile("e5")));
+    assert_eq!(
+        set.into_iter().collect::<Vec<_>>(),
+        [tile("a8"), tile("e4"), tile("h1")]
+    );
+}
+
+#[test]
+fn leaper_attacks() {
+    assert_eq!(bitboard::knight_attacks(tile("a1")), tiles(&["b3", "c2"]));
+    assert_eq!(
+        bitboard::king_attacks(tile("h8")),
+        tiles(&["g8", "g7", "h7"])
+    );
+    assert_eq!(
+        bitboard::pawn_attacks(Color::White, tile("e4")),
+        tiles(&["d5", "f5"])
+    );
+    assert_eq!(
+        bitboard::pawn_attacks(Color::Black, tile("a7")),
+        tiles(&["b6"])
+    );
+}
+
+#[test]
+fn slider_attacks_stop_at_blockers() {
+    let occupied = tiles(&["d6", "f4", "b2"]);
+    assert_eq!(
+        bitboard::rook_attacks(tile("d4"), occupied),
+        tiles(&["d5", "d6", "e4", "f4", "c4", "b4", "a4", "d3", "d2", "d1"])
+    );
+    assert_eq!(
+        bitboard::bishop_attacks(tile("d4"), occupied),
+        tiles(&["e5", "f6", "g7", "h8", "c5", "b6", "a7", "e3", "f2", "g1", "c3", "b2"])
+    );
+}
+
+#[test]
+fn kept_up_to_date() {
+    let mut state = Chess::new();
+    for san in [
+        "e4", "d5", "exd5", "Qxd5", "Nf3", "Bg4", "Be2", "Qe5", "O-O",
+    ] {
+        state.perform(state.parse_san(san).unwrap());
+    }
+
+    // The bitboards agree with a position set up from scratch
+    let fresh = Chess::from_fen(&state.to_fen()).unwrap();
+    for color in [Color::White, Color::Black] {
+        for kind in [
+            Kind::Pawn,
+            Kind::Rook,
+            Kind::Knight,
+            Kind::Bishop,
+            Kind::Queen,
+            Kind::King,
+        ] {
+            let piece = Piece::new(color, kind);
+            assert_eq!(state.bitboard(piece), fresh.bitboard(piece));
+        }
+    }
+    assert_eq!(state.bitboard(Piece::WHITE_KING), tiles(&["g1"]));
+    assert_eq!(state.occupied().len(), 30);
+}
@@ -1,230 +1,23 @@
-use schaakmaat::chess::{Chess, Color, Outcome, Piece};
-use schaakmaat::computer;
-use schaakmaat::pos::Pos;
+use schaakmaat::chess::{Chess, Color, Outcome};
+use schaakmaat::computer::{self, Score};
+use schaakmaat::fen::STARTING_POSITION;
+use schaakmaat::transposition::TranspositionTable;
 
-const MATE_IN_THREE: Chess = Chess {
-    board: [
-        [
-            Some(Piece::BLACK_ROOK),
-            None,
-            Some(Piece::BLACK_BISHOP),
-            Some(Piece::BLACK_QUEEN),
-            None,
-            Some(Piece::BLACK_KNIGHT),
-            None,
-            Some(Piece::BLACK_ROOK),
-        ],
-        [
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::BLACK_PAWN),
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_QUEEN),
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::WHITE_BISHOP),
-        ],
-        [
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::BLACK_BISHOP),
-            None,
-            None,
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_ROOK),
-            None,
-            None,
-        ],
-        [
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::BLACK_KING),
-            None,
-            None,
-            None,
-        ],
-        [None, None, None, None, None, None, None, None],
-        [
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-        ],
-        [
-            Some(Piece::WHITE_ROOK),
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_KING),
-            None,
-        ],
-    ],
-    turn: Color::White,
-    kings: [Pos::new(6, 7), Pos::new(4, 5)],
-};
+const MATE_IN_THREE: &str = "r1bq1n1r/pp3QpB/2p1pb2/5R2/2pPk3/8/PPP3PP/R5K1 w - - 0 1";
 
-const MATE_IN_TWO: Chess = Chess {
-    board: [
-        [
-            None,
-            None,
-            None,
-            Some(Piece::BLACK_ROOK),
-            None,
-            None,
-            Some(Piece::BLACK_ROOK),
-            Some(Piece::BLACK_KING),
-        ],
-        [
-            Some(Piece::BLACK_PAWN),
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-            Some(Piece::BLACK_PAWN),
-        ],
-        [
-            None,
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::BLACK_PAWN),
-            None,
-        ],
-        [
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_ROOK),
-            None,
-            None,
-            None,
-            None,
-        ],
-        [
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-            Some(Piece::WHITE_BISHOP),
-            None,
-            None,
-            Some(Piece::WHITE_QUEEN),
-        ],
-        [None, None, None, None, None, None, None, None],
-        [
-            None,
-            Some(Piece::BLACK_QUEEN),
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-        ],
-        [
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_ROOK),
-            None,
-            Some(Piece::WHITE_KING),
-            None,
-        ],
-    ],
-    turn: Color::White,
-    kings: [Pos::new(6, 7), Pos::new(7, 0)],
-};
+const MATE_IN_TWO: &str = "3r2rk/p4p1p/3p1Pp1/3R4/2p1B2Q/8/1q4PP/4R1K1 w - - 0 1";
 
-const MATE_IN_ONE: Chess = Chess {
-    board: [
-        [
-            Some(Piece::BLACK_ROOK),
-            None,
-            Some(Piece::BLACK_BISHOP),
-            None,
-            None,
-            Some(Piece::BLACK_ROOK),
-            Some(Piece::BLACK_KING),
-            None,
-        ],
-        [
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::BLACK_PAWN),
-            Some(Piece::BLACK_PAWN),
-            None,
-            None,
-            Some(Piece::BLACK_PAWN),
-            None,
-        ],
-        [None, None, None, None, None, None, None, None],
-        [
-            None,
-            None,
-            None,
-            Some(Piece::BLACK_QUEEN),
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_KNIGHT),
-            None,
-            Some(Piece::WHITE_QUEEN),
-        ],
-        [None, None, None, None, None, None, None, None],
-        [None, None, None, None, None, None, None, None],
-        [
-            Some(Piece::WHITE_PAWN),
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Piece::WHITE_PAWN),
-            Some(Piece::WHITE_PAWN),
-        ],
-        [
-            Some(Piece::BLACK_BISHOP),
-            None,
-            Some(Piece::WHITE_BISHOP),
-            None,
-            None,
-            Some(Piece::WHITE_ROOK),
-            None,
-            Some(Piece::WHITE_KING),
-        ],
-    ],
-    turn: Color::White,
-    kings: [Pos::new(7, 7), Pos::new(6, 0)],
-};
+const MATE_IN_ONE: &str = "r1b2rk1/pppp2p1/8/3qPN1Q/8/8/P5PP/b1B2R1K w - - 0 1";
+
+/// Black can check forever with Qe1+ Kh2 Qh4+ Kg1, which repeats the position after four moves.
+const PERPETUAL_CHECK: &str = "7k/1R4pp/1Q6/8/7q/8/6P1/6K1 b - - 0 1";
 
 #[test]
-#[ignore]
 fn mate_in_three() {
-    let mut state = MATE_IN_THREE;
+    let mut state = Chess::from_fen(MATE_IN_THREE).unwrap();
 
     for _ in 0..5 {
-        let best_move = computer::minimax(&state, 5).m.unwrap();
+        let best_move = computer::alpha_beta(&state, 5).m.unwrap();
         state.perform(best_move);
     }
 
@@ -233,7 +26,7 @@ fn mate_in_three() {
 
 #[test]
 fn mate_in_two() {
-    let mut state = MATE_IN_TWO;
+    let mut state = Chess::from_fen(MATE_IN_TWO).unwrap();
 
     for _ in 0..3 {
         let best_move = computer::minimax(&state, 3).m.unwrap();
@@ -245,7 +38,7 @@ fn mate_in_two() {
 
 #[test]
 fn mate_in_one() {
-    let mut state = MATE_IN_ONE;
+    let mut state = Chess::from_fen(MATE_IN_ONE).unwrap();
 
     for _ in 0..1 {
         let best_move = computer::minimax(&state, 1).m.unwrap();
@@ -254,3 +47,52 @@ fn mate_in_one() {
 
     assert_eq!(state.outcome(), Some(Outcome::Winner(Color::White)));
 }
+
+#[test]
+fn mate_in_three_with_table() {
+    let mut state = Chess::from_fen(MATE_IN_THREE).unwrap();
+    let mut table = TranspositionTable::new(16);
+
+    for _ in 0..5 {
+        let best_move = computer::alpha_beta_with_table(&state, 5, &mut table)
+            .m
+            .unwrap();
+        state.perform(best_move);
+    }
+
+    assert_eq!(state.outcome(), Some(Outcome::Winner(Color::White)));
+}
+
+#[test]
+fn alpha_beta_matches_minimax() {
+    for fen in [MATE_IN_TWO, MATE_IN_ONE, PERPETUAL_CHECK, STARTING_POSITION] {
+        let state = Chess::from_fen(fen).unwrap();
+        for depth in 1..=4 {
+            assert_eq!(
+                computer::alpha_beta(&state, depth),
+                computer::minimax(&state, depth)
+            );
+        }
+    }
+}
+
+#[test]
+fn mate_scores() {
+    let state = Chess::from_fen(MATE_IN_TWO).unwrap();
+    let score = computer::alpha_beta(&state, 5).score;
+    assert_eq!(score.mate_in(), Some(2));
+    assert_eq!(score.to_string(), "mate 2");
+    assert_eq!((-score).to_string(), "mate -2");
+
+    // A quicker mate is better for the winner, and a slower one better for the loser
+    assert!(Score::mate(Color::White, 1) > Score::mate(Color::White, 3));
+    assert!(Score::mate(Color::Black, 1) < Score::mate(Color::Black, 3));
+    assert!(Score::mate(Color::White, 3) > Score::centipawns(3900));
+    assert_eq!(-Score::MIN, Score::MAX);
+
+    assert_eq!(Score::centipawns(-35).to_string(), "cp -35");
+    assert_eq!(Score::centipawns(i16::MIN).mate_in(), None);
+    assert_eq!(Score::centipawns(29_500).to_string(), "cp 28999");
+    assert!(Score::centipawns(i16::MAX) < Score::mate(Color::White, 1_000));
+    assert_eq!(Chess::new().evaluate(), Score::DRAW);
+}
@@ -0,0 +1,61 @@
+use schaakmaat::chess::{Chess, Kind, Piece};
+use schaakmaat::computer::Score;
+use schaakmaat::evaluation::{self, Tapered, MAX_PHASE};
+use schaakmaat::pos::Pos;
+
+#[test]
+fn symmetric() {
+    assert_eq!(Chess::new().evaluate(), Score::DRAW);
+
+    // The same position with the colors swapped and the board turned upside down
+    let white = Chess::from_fen("4k3/8/8/4N3/8/8/3P4/4K3 w - - 0 1").unwrap();
+    let black = Chess::from_fen("4k3/3p4/8/8/4n3/8/8/4K3 b - - 0 1").unwrap();
+    assert_eq!(white.evaluate(), -black.evaluate());
+}
+
+#[test]
+fn centralized_pieces() {
+    let knight =
+        |name| evaluation::piece_value(Piece::WHITE_KNIGHT, Pos::from_algebraic(name).unwrap());
+    assert!(knight("e5").middlegame > knight("a1").middlegame);
+    assert_eq!(knight("e5"), Tapered::new(340, 320));
+
+    let pawn =
+        |name| evaluation::piece_value(Piece::BLACK_PAWN, Pos::from_algebraic(name).unwrap());
+    assert!(pawn("e5").middlegame < pawn("e7").middlegame);
+}
+
+#[test]
+fn piece_values() {
+    assert_eq!(Kind::Pawn.value(), Tapered::new(100, 120));
+    assert_eq!(Piece::BLACK_QUEEN.value(), -Kind::Queen.value());
+    assert!(Kind::Knight.value().middlegame < Kind::Rook.value().middlegame);
+}
+
+#[test]
+fn phase() {
+    assert_eq!(Chess::new().phase(), MAX_PHASE);
+    let rook_ending = Chess::from_fen("4k3/pp3r2/8/8/8/8/PP6/3RK3 w - - 0 1").unwrap();
+    assert_eq!(rook_ending.phase(), 4);
+    let pawn_ending = Chess::from_fen("4k3/pp6/8/8/8/8/PP6/4K3 w - - 0 1").unwrap();
+    assert_eq!(pawn_ending.phase(), 0);
+
+    let score = Tapered::new(100, -100);
+    assert_eq!(score.blend(MAX_PHASE), 100);
+    assert_eq!(score.blend(MAX_PHASE / 2), 0);
+    assert_eq!(score.blend(0), -100);
+}
+
+#[test]
+fn king_centralizes_in_endgame() {
+    let king =
+        |name| evaluation::piece_value(Piece::WHITE_KING, Pos::from_algebraic(name).unwrap());
+
+    // With all pieces on the board, the castled king is safer than a central one
+    assert!(king("g1").blend(MAX_PHASE) > king("e3").blend(MAX_PHASE));
+
+    // Without any pieces, the king belongs in the center
+    let castled = Chess::from_fen("4k3/pp6/8/8/8/8/PP6/6K1 w - - 0 1").unwrap();
+    let central = Chess::from_fen("4k3/pp6/8/8/8/4K3/PP6/8 w - - 0 1").unwrap();
+    assert!(castled.evaluate() < central.evaluate());
+}
@@ -0,0 +1,161 @@
+use schaakmaat::chess::{Chess, Color};
+use schaakmaat::fen::{FenError, STARTING_POSITION};
+use schaakmaat::pos::Pos;
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+#[test]
+fn starting_position() {
+    assert_eq!(Chess::from_fen(STARTING_POSITION), Ok(Chess::new()));
+    assert_eq!(Chess::new().to_fen(), STARTING_POSITION);
+}
+
+#[test]
+fn round_trip() {
+    for fen in [
+        KIWIPETE,
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "4k3/8/8/8/8/8/8/4K2R b K - 12 40",
+    ] {
+        assert_eq!(Chess::from_fen(fen).unwrap().to_fen(), fen);
+    }
+}
+
+#[test]
+fn kings_from_board() {
+    let state = Chess::from_fen(KIWIPETE).unwrap();
+    assert_eq!(state.kings(), [Pos::new(4, 7), Pos::new(4, 0)]);
+}
+
+#[test]
+fn counters_after_moves() {
+    let mut state = Chess::new();
+    let leap = state
+        .moves()
+        .find(|m| m.from() == Pos::new(4, 6) && m.to() == Pos::new(4, 4))
+        .unwrap();
+    state.perform(leap);
+    assert_eq!(
+        state.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+
+    let knight = state
+        .moves()
+        .find(|m| m.from() == Pos::new(6, 0) && m.to() == Pos::new(5, 2))
+        .unwrap();
+    state.perform(knight);
+    assert_eq!(
+        state.to_fen(),
+        "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 1 2"
+    );
+}
+
+#[test]
+fn counters_saturate() {
+    let mut state = Chess::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 65535 65535").unwrap();
+    let m = state.moves().next().unwrap();
+    state.perform(m);
+    assert_eq!(state.halfmove_clock(), u16::MAX);
+    assert_eq!(state.fullmove_number(), u16::MAX);
+}
+
+#[test]
+fn optional_counters() {
+    let state = Chess::from_fen("4k3/8/8/8/8/8/8/4K3 b - -").unwrap();
+    assert_eq!(state.turn(), Color::Black);
+    assert_eq!(state.halfmove_clock(), 0);
+    assert_eq!(state.fullmove_number(), 1);
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(Chess::from_fen(""), Err(FenError::MissingField));
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1 x"),
+        Err(FenError::TrailingField)
+    );
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/8/8/4K3 w - - 0 1"),
+        Err(FenError::InvalidBoard)
+    );
+    assert_eq!(
+        Chess::from_fen("4k4/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Err(FenError::InvalidBoard)
+    );
+    assert_eq!(
+        Chess::from_fen("4x3/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Err(FenError::InvalidPiece('x'))
+    );
+    assert_eq!(
+        Chess::from_fen("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Err(FenError::InvalidKings(Color::Black))
+    );
+    assert_eq!(
+        Chess::from_fen("P3k3/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Err(FenError::InvalidPawn)
+    );
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/8/8/8/4K2p w - - 0 1"),
+        Err(FenError::InvalidPawn)
+    );
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/8/8/8/4K3 x - - 0 1"),
+        Err(FenError::InvalidTurn)
+    );
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/8/8/8/4K3 w X - 0 1"),
+        Err(FenError::InvalidCastling)
+    );
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/8/8/8/4K3 w KQ - 0 1"),
+        Err(FenError::ImpossibleCastling)
+    );
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQq - 0 1"),
+        Err(FenError::ImpossibleCastling)
+    );
+    assert_eq!(
+        Chess::from_fen("r3k2r/8/8/8/8/8/8/R4K1R w K - 0 1"),
+        Err(FenError::ImpossibleCastling)
+    );
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/8/8/8/4K3 w - e4 0 1"),
+        Err(FenError::InvalidEnPassant)
+    );
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1"),
+        Err(FenError::InvalidCheck)
+    );
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/8/8/8/4K3 w - - x 1"),
+        Err(FenError::InvalidCounter)
+    );
+}
+
+#[test]
+fn en_passant_needs_a_leap() {
+    let fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2";
+    assert_eq!(Chess::from_fen(fen).unwrap().to_fen(), fen);
+
+    // No pawn beyond the tile
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/4P3/8/8/8/4K3 w - d6 0 1"),
+        Err(FenError::InvalidEnPassant)
+    );
+    // The tile is on the row of the player to move
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/3pP3/8/8/8/4K3 b - d6 0 1"),
+        Err(FenError::InvalidEnPassant)
+    );
+    assert_eq!(
+        Chess::from_fen("4k3/8/8/8/3Pp3/8/8/4K3 w - d3 0 1"),
+        Err(FenError::InvalidEnPassant)
+    );
+    // The pawn cannot have come from an occupied tile
+    assert_eq!(
+        Chess::from_fen("4k3/3n4/8/3pP3/8/8/8/4K3 w - d6 0 1"),
+        Err(FenError::InvalidEnPassant)
+    );
+}
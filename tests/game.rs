@@ -0,0 +1,109 @@
+use schaakmaat::chess::{Chess, Color, DrawReason, Outcome};
+use schaakmaat::computer::Score;
+use schaakmaat::game::Game;
+
+fn play(game: &mut Game, moves: &[&str]) {
+    for san in moves {
+        game.perform(game.chess().parse_san(san).unwrap());
+    }
+}
+
+#[test]
+fn threefold_repetition() {
+    let mut game = Game::new();
+    play(&mut game, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+    assert_eq!(game.repetitions(), 2);
+    assert_eq!(game.outcome(), None);
+
+    play(&mut game, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+    assert_eq!(game.repetitions(), 3);
+    assert_eq!(
+        game.outcome(),
+        Some(Outcome::Draw(DrawReason::ThreefoldRepetition))
+    );
+
+    play(
+        &mut game,
+        &["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1", "Ng8"],
+    );
+    assert_eq!(
+        game.outcome(),
+        Some(Outcome::Draw(DrawReason::FivefoldRepetition))
+    );
+}
+
+#[test]
+fn pawn_moves_reset_repetitions() {
+    let mut game = Game::new();
+    play(&mut game, &["Nf3", "Nf6", "Ng1", "Ng8", "e4"]);
+    play(&mut game, &["Nf6", "Nf3", "Ng8", "Ng1", "Nf6"]);
+    assert_eq!(game.repetitions(), 2);
+}
+
+#[test]
+fn undo() {
+    let mut game = Game::new();
+    play(&mut game, &["e4", "e5"]);
+    let m = game.chess().parse_san("Nf3").unwrap();
+    game.perform(m);
+
+    assert_eq!(game.undo(), Some(m));
+    assert_eq!(game.moves().count(), 2);
+    assert_eq!(
+        *game.chess(),
+        Chess::from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2").unwrap()
+    );
+
+    game.undo();
+    game.undo();
+    assert_eq!(*game.chess(), Chess::new());
+    assert_eq!(game.undo(), None);
+}
+
+#[test]
+fn move_rules() {
+    let mut game = Game::from(Chess::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap());
+    assert_eq!(game.outcome(), None);
+    play(&mut game, &["Ra2"]);
+    assert_eq!(game.outcome(), Some(Outcome::Draw(DrawReason::FiftyMoves)));
+
+    let game = Game::from(Chess::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 150 105").unwrap());
+    assert_eq!(
+        game.outcome(),
+        Some(Outcome::Draw(DrawReason::SeventyFiveMoves))
+    );
+
+    // Mating with the last move still wins
+    let game = Game::from(Chess::from_fen("R3k3/8/4K3/8/8/8/8/8 b - - 100 80").unwrap());
+    assert_eq!(game.outcome(), Some(Outcome::Winner(Color::White)));
+}
+
+#[test]
+fn insufficient_material() {
+    let dead = [
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4KN2 w - - 0 1",
+        "4kb2/8/8/8/8/8/8/4K3 b - - 0 1",
+        // Both bishops are on dark tiles
+        "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+    ];
+    for fen in dead {
+        let state = Chess::from_fen(fen).unwrap();
+        assert_eq!(
+            state.outcome(),
+            Some(Outcome::Draw(DrawReason::InsufficientMaterial)),
+            "{fen}"
+        );
+        assert_eq!(state.evaluate(), Score::DRAW);
+    }
+
+    let alive = [
+        "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4KNN1 w - - 0 1",
+        "4kb2/8/8/8/8/8/8/3BK3 w - - 0 1",
+        "4kn2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+    ];
+    for fen in alive {
+        assert_eq!(Chess::from_fen(fen).unwrap().outcome(), None, "{fen}");
+    }
+}
@@ -0,0 +1,41 @@
+use schaakmaat::chess::Chess;
+use schaakmaat::evaluation::Tapered;
+use schaakmaat::king_safety::king_safety;
+
+fn safety(fen: &str) -> Tapered {
+    king_safety(&Chess::from_fen(fen).unwrap())
+}
+
+#[test]
+fn pawn_shield() {
+    assert_eq!(
+        safety("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1"),
+        Tapered::default()
+    );
+
+    // The pawn in front of the king is worth less once it has advanced
+    let advanced = safety("6k1/5ppp/8/8/8/6P1/5P1P/6K1 w - - 0 1");
+    assert_eq!(advanced, Tapered::new(-6, 0));
+}
+
+#[test]
+fn open_files() {
+    // Without the pawn in front of it, the king is exposed along a column black's rooks can use
+    let half_open = safety("6k1/5ppp/8/8/8/8/5P1P/6K1 w - - 0 1");
+    assert_eq!(half_open, Tapered::new(-27, 0));
+
+    // With both columns open, neither king is better off
+    assert_eq!(
+        safety("6k1/5p1p/8/8/8/8/5P1P/6K1 w - - 0 1"),
+        Tapered::default()
+    );
+}
+
+#[test]
+fn attacked_king_zone() {
+    let far = safety("6k1/5ppp/8/q7/8/8/5PPP/6K1 w - - 0 1");
+    let near = safety("6k1/5ppp/8/8/7q/8/5PPP/6K1 w - - 0 1");
+    assert_eq!(far, Tapered::default());
+    assert!(near.middlegame < far.middlegame);
+    assert_eq!(near.endgame, 0);
+}
@@ -0,0 +1,160 @@
+use schaakmaat::chess::{Castling, Chess, Kind, Move, MoveList, Piece};
+use schaakmaat::pos::Pos;
+
+fn find_move(state: &Chess, from: Pos, to: Pos) -> Option<Move> {
+    state.moves().find(|m| m.from() == from && m.to() == to)
+}
+
+#[test]
+fn castle_king_side() {
+    let mut state =
+        Chess::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQK2R w KQkq - 0 1").unwrap();
+
+    let castle = find_move(&state, Pos::new(4, 7), Pos::new(6, 7)).unwrap();
+    state.perform(castle);
+
+    assert_eq!(state[Pos::new(6, 7)], Some(Piece::WHITE_KING));
+    assert_eq!(state[Pos::new(5, 7)], Some(Piece::WHITE_ROOK));
+    assert_eq!(state[Pos::new(7, 7)], None);
+    assert_eq!(state.castling()[0], Castling::NONE);
+}
+
+#[test]
+fn castle_queen_side() {
+    let mut state =
+        Chess::from_fen("r3kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1").unwrap();
+
+    let castle = find_move(&state, Pos::new(4, 0), Pos::new(2, 0)).unwrap();
+    state.perform(castle);
+
+    assert_eq!(state[Pos::new(2, 0)], Some(Piece::BLACK_KING));
+    assert_eq!(state[Pos::new(3, 0)], Some(Piece::BLACK_ROOK));
+    assert_eq!(state[Pos::new(0, 0)], None);
+    assert_eq!(state.castling()[1], Castling::NONE);
+}
+
+#[test]
+fn castle_not_through_check() {
+    // A black rook on the f-file attacks the tile the king has to pass through
+    let state = Chess::from_fen("rnbqkbnr/ppppprpp/8/8/8/8/PPPPP1PP/RNBQK2R w KQkq - 0 1").unwrap();
+
+    assert!(find_move(&state, Pos::new(4, 7), Pos::new(6, 7)).is_none());
+}
+
+#[test]
+fn castle_rights_lost_after_rook_move() {
+    let mut state =
+        Chess::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQK2R w KQkq - 0 1").unwrap();
+
+    state.perform(find_move(&state, Pos::new(7, 7), Pos::new(6, 7)).unwrap());
+    state.perform(find_move(&state, Pos::new(0, 1), Pos::new(0, 2)).unwrap());
+    state.perform(find_move(&state, Pos::new(6, 7), Pos::new(7, 7)).unwrap());
+    state.perform(find_move(&state, Pos::new(0, 2), Pos::new(0, 3)).unwrap());
+
+    assert!(!state.castling()[0].king_side);
+    assert!(find_move(&state, Pos::new(4, 7), Pos::new(6, 7)).is_none());
+}
+
+#[test]
+fn en_passant_capture() {
+    let mut state = Chess::new();
+
+    state.perform(find_move(&state, Pos::new(4, 6), Pos::new(4, 4)).unwrap());
+    state.perform(find_move(&state, Pos::new(0, 1), Pos::new(0, 2)).unwrap());
+    state.perform(find_move(&state, Pos::new(4, 4), Pos::new(4, 3)).unwrap());
+    state.perform(find_move(&state, Pos::new(3, 1), Pos::new(3, 3)).unwrap());
+    assert_eq!(state.en_passant(), Some(Pos::new(3, 2)));
+
+    state.perform(find_move(&state, Pos::new(4, 3), Pos::new(3, 2)).unwrap());
+    assert_eq!(state[Pos::new(3, 2)], Some(Piece::WHITE_PAWN));
+    assert_eq!(state[Pos::new(3, 3)], None);
+    assert_eq!(state.en_passant(), None);
+}
+
+#[test]
+fn en_passant_expires() {
+    let mut state = Chess::new();
+
+    state.perform(find_move(&state, Pos::new(4, 6), Pos::new(4, 4)).unwrap());
+    state.perform(find_move(&state, Pos::new(0, 1), Pos::new(0, 2)).unwrap());
+    state.perform(find_move(&state, Pos::new(4, 4), Pos::new(4, 3)).unwrap());
+    state.perform(find_move(&state, Pos::new(3, 1), Pos::new(3, 3)).unwrap());
+    state.perform(find_move(&state, Pos::new(7, 6), Pos::new(7, 5)).unwrap());
+    state.perform(find_move(&state, Pos::new(0, 2), Pos::new(0, 3)).unwrap());
+
+    assert!(find_move(&state, Pos::new(4, 3), Pos::new(3, 2)).is_none());
+}
+
+#[test]
+fn promotion() {
+    let mut state =
+        Chess::from_fen("1nbqkbnr/Pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQk - 0 1").unwrap();
+
+    let promotions: Vec<Move> = state
+        .moves()
+        .filter(|m| m.from() == Pos::new(0, 1))
+        .collect();
+    assert_eq!(promotions.len(), 8);
+    assert!(promotions.iter().all(|m| m.promotion().is_some()));
+
+    let promotion = promotions
+        .into_iter()
+        .find(|m| m.to() == Pos::new(1, 0) && m.promotion() == Some(Kind::Knight))
+        .unwrap();
+    state.perform(promotion);
+    assert_eq!(state[Pos::new(1, 0)], Some(Piece::WHITE_KNIGHT));
+    assert_eq!(state[Pos::new(0, 1)], None);
+}
+
+#[test]
+fn pinned_piece_stays_on_line() {
+    // The bishop on d2 is pinned by the black bishop on b4, so it may only move towards it
+    let state = Chess::from_fen("4k3/8/8/8/1b6/8/3B4/4K3 w - - 0 1").unwrap();
+    let bishop: Vec<Pos> = state
+        .moves()
+        .filter(|m| m.from() == Pos::new(3, 6))
+        .map(|m| m.to())
+        .collect();
+    assert_eq!(bishop, [Pos::new(1, 4), Pos::new(2, 5)]);
+}
+
+#[test]
+fn en_passant_not_into_check() {
+    // Taking en passant would take both pawns off the row the king and the rook are on
+    let state = Chess::from_fen("8/8/8/K2pP2r/8/8/8/7k w - d6 0 1").unwrap();
+    assert_eq!(find_move(&state, Pos::new(4, 3), Pos::new(3, 2)), None);
+}
+
+#[test]
+fn generate_into_reuses_list() {
+    let mut moves = MoveList::new();
+    Chess::new().generate_into(&mut moves);
+    assert_eq!(moves.len(), 20);
+
+    // Only the king can move out of a double check
+    let state = Chess::from_fen("4k3/8/8/8/8/5n2/8/r3K3 w - - 0 1").unwrap();
+    state.generate_into(&mut moves);
+    assert_eq!(moves.len(), 2);
+    assert!(moves.iter().all(|m| m.from() == Pos::new(4, 7)));
+}
+
+#[test]
+fn unmake_move_restores_position() {
+    // Castling both ways, en passant and promotions with and without capturing
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    ] {
+        let mut state = Chess::from_fen(fen).unwrap();
+        let before = state;
+        for m in before.moves() {
+            let undo = state.make_move(m);
+            assert_eq!(undo.m(), m);
+            assert_eq!(undo.captured().is_some(), before.is_capture(m));
+            state.unmake_move(undo);
+            assert_eq!(state, before, "{fen} {}", m.to_uci());
+        }
+    }
+}
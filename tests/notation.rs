@@ -0,0 +1,151 @@
+use schaakmaat::chess::{Chess, Kind, Move, Piece};
+use schaakmaat::fen::STARTING_POSITION;
+use schaakmaat::pos::Pos;
+use schaakmaat::san::SanError;
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+/// Plays a game from the given position, checking that every move is written the way it is read.
+fn play(fen: &str, moves: &[&str]) -> Chess {
+    let mut state = Chess::from_fen(fen).unwrap();
+    for san in moves {
+        let m = state.parse_san(san).unwrap();
+        assert_eq!(state.move_to_san(m), *san);
+        state.perform(m);
+    }
+    state
+}
+
+#[test]
+fn san_opening() {
+    let state = play(
+        STARTING_POSITION,
+        &[
+            "e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Bxc6", "dxc6", "O-O", "Bg4",
+        ],
+    );
+    assert_eq!(
+        state.to_fen(),
+        "r2qkbnr/1pp2ppp/p1p5/4p3/4P1b1/5N2/PPPP1PPP/RNBQ1RK1 w kq - 2 6"
+    );
+}
+
+#[test]
+fn san_kiwipete() {
+    let state = Chess::from_fen(KIWIPETE).unwrap();
+    let mut sans: Vec<String> = state.moves().map(|m| state.move_to_san(m)).collect();
+    sans.sort();
+
+    assert_eq!(sans.len(), 48);
+    for san in [
+        "O-O", "O-O-O", "Bxa6", "Nxf7", "Qxf6", "dxe6", "Rb1", "Kd1", "gxh3",
+    ] {
+        assert!(sans.contains(&san.to_owned()), "missing {san}");
+    }
+    for san in &sans {
+        assert_eq!(state.move_to_san(state.parse_san(san).unwrap()), *san);
+    }
+}
+
+#[test]
+fn san_disambiguation() {
+    let state = Chess::from_fen("4k3/8/8/8/1N3N2/8/R6R/4K3 w - - 0 1").unwrap();
+
+    assert_eq!(state.move_to_san(state.parse_san("Nbd3").unwrap()), "Nbd3");
+    assert_eq!(state.move_to_san(state.parse_san("Rad2").unwrap()), "Rad2");
+    assert_eq!(state.parse_san("Nd3"), Err(SanError::AmbiguousMove));
+
+    let state = Chess::from_fen("4k3/8/8/8/1N6/8/1N6/4K3 w - - 0 1").unwrap();
+    assert_eq!(state.move_to_san(state.parse_san("N4d3").unwrap()), "N4d3");
+}
+
+#[test]
+fn san_promotion_and_mate() {
+    let state = Chess::from_fen("6k1/4P3/6K1/8/8/8/8/8 w - - 0 1").unwrap();
+    let m = state.parse_san("e8=Q+").unwrap();
+    assert_eq!(m.promotion(), Some(Kind::Queen));
+    assert_eq!(state.move_to_san(m), "e8=Q#");
+
+    let m = state.parse_san("e8=N").unwrap();
+    assert_eq!(m.to(), Pos::new(4, 0));
+    assert_eq!(state.move_to_san(m), "e8=N");
+}
+
+#[test]
+fn san_en_passant() {
+    let state = play(STARTING_POSITION, &["e4", "a6", "e5", "d5", "exd6"]);
+    assert_eq!(state[Pos::new(3, 3)], None);
+}
+
+#[test]
+fn san_invalid() {
+    let state = Chess::new();
+    assert_eq!(state.parse_san("e5"), Err(SanError::IllegalMove));
+    assert_eq!(state.parse_san("O-O"), Err(SanError::IllegalMove));
+    assert_eq!(state.parse_san("Zf3"), Err(SanError::InvalidSyntax));
+    assert_eq!(state.parse_san("e9"), Err(SanError::InvalidSyntax));
+    assert_eq!(state.parse_san("e8=K"), Err(SanError::InvalidSyntax));
+}
+
+#[test]
+fn algebraic_tiles() {
+    let state = Chess::new();
+    assert_eq!(Pos::from_algebraic("a8"), Some(Pos::new(0, 0)));
+    assert_eq!(Pos::from_algebraic("h1"), Some(Pos::new(7, 7)));
+    assert_eq!(
+        state[Pos::from_algebraic("e1").unwrap()],
+        Some(Piece::WHITE_KING)
+    );
+    assert_eq!(
+        state[Pos::from_algebraic("d8").unwrap()],
+        Some(Piece::BLACK_QUEEN)
+    );
+    assert_eq!(Pos::from_algebraic("i1"), None);
+    assert_eq!(Pos::from_algebraic("a0"), None);
+
+    for x in 0..8 {
+        for y in 0..8 {
+            let pos = Pos::new(x, y);
+            assert_eq!(Pos::from_algebraic(&pos.to_algebraic()), Some(pos));
+        }
+    }
+}
+
+#[test]
+fn uci_round_trip() {
+    let state = Chess::new();
+    let mut ucis: Vec<String> = state.moves().map(|m| m.to_uci()).collect();
+    ucis.sort();
+    assert_eq!(
+        ucis,
+        [
+            "a2a3", "a2a4", "b1a3", "b1c3", "b2b3", "b2b4", "c2c3", "c2c4", "d2d3", "d2d4", "e2e3",
+            "e2e4", "f2f3", "f2f4", "g1f3", "g1h3", "g2g3", "g2g4", "h2h3", "h2h4"
+        ]
+    );
+
+    for m in state.moves() {
+        assert_eq!(Move::from_uci(&m.to_uci()), Some(m));
+    }
+
+    let m = Move::from_uci("e2e4").unwrap();
+    assert_eq!(m.from(), Pos::new(4, 6));
+    assert_eq!(m.to(), Pos::new(4, 4));
+}
+
+#[test]
+fn uci_special_moves() {
+    let state = Chess::from_fen(KIWIPETE).unwrap();
+    let castle = state.parse_san("O-O").unwrap();
+    assert_eq!(castle.to_uci(), "e1g1");
+    assert_eq!(Move::from_uci("e1g1"), Some(castle));
+
+    let state = Chess::from_fen("6k1/4P3/6K1/8/8/8/8/8 w - - 0 1").unwrap();
+    let promotion = Move::from_uci("e7e8n").unwrap();
+    assert_eq!(promotion.promotion(), Some(Kind::Knight));
+    assert!(state.moves().any(|m| m == promotion));
+    assert_eq!(promotion.to_uci(), "e7e8n");
+
+    assert_eq!(Move::from_uci("e7e8k"), None);
+    assert_eq!(Move::from_uci("e7"), None);
+}
@@ -0,0 +1,72 @@
+use schaakmaat::bitboard::Bitboard;
+use schaakmaat::chess::Chess;
+use schaakmaat::evaluation::Tapered;
+use schaakmaat::pawns::{PawnStructure, PawnTable};
+use schaakmaat::pos::Pos;
+
+fn structure(fen: &str) -> PawnStructure {
+    PawnStructure::new(&Chess::from_fen(fen).unwrap())
+}
+
+fn tile(name: &str) -> Bitboard {
+    Bitboard::from(Pos::from_algebraic(name).unwrap())
+}
+
+#[test]
+fn doubled_and_isolated() {
+    // Both pawns are isolated, the rear one is doubled, and the front one is passed
+    let pawns = structure("4k3/8/8/8/8/P7/P7/4K3 w - - 0 1");
+    assert_eq!(pawns.score, Tapered::new(-25, -35));
+    assert_eq!(pawns.passed, tile("a3"));
+}
+
+#[test]
+fn backward() {
+    // White's pawn on d3 cannot advance past black's pawn on e5, which is isolated
+    let pawns = structure("4k3/8/8/4p3/4P3/3P4/8/4K3 w - - 0 1");
+    assert_eq!(pawns.score, Tapered::new(2, 5));
+    assert!(pawns.passed.is_empty());
+}
+
+#[test]
+fn passed() {
+    let free = structure("4k3/8/8/3P4/8/8/8/4K3 w - - 0 1");
+    assert_eq!(free.passed, tile("d5"));
+
+    // An enemy pawn on a neighboring column can still take it
+    let guarded = structure("4k3/8/2p5/3P4/8/8/8/4K3 w - - 0 1");
+    assert_eq!(guarded.passed, Bitboard::EMPTY);
+
+    // Further advanced passed pawns are worth more, and black's count the other way
+    let advanced = structure("7k/3P4/8/8/8/8/8/4K3 w - - 0 1");
+    assert!(advanced.score.endgame > free.score.endgame);
+    let black = structure("4k3/8/8/8/3p4/8/8/4K3 w - - 0 1");
+    assert_eq!(black.score, -free.score);
+}
+
+#[test]
+fn blocked_passed_pawn() {
+    let free = Chess::from_fen("4k3/8/8/3P4/8/8/8/4K3 w - - 0 1").unwrap();
+    let blocked = Chess::from_fen("8/3k4/8/3P4/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(PawnStructure::new(&free).blocked(&free), Tapered::default());
+    assert!(PawnStructure::new(&blocked).blocked(&blocked).endgame < 0);
+    assert!(free.evaluate() > blocked.evaluate());
+}
+
+#[test]
+fn table() {
+    let mut table = PawnTable::new(1);
+    let mut state = Chess::new();
+    let structure = table.get(&state);
+    assert_eq!(structure, PawnStructure::new(&state));
+
+    // Moving a knight leaves the pawns alone
+    let pawns = state.pawn_zobrist();
+    state.perform(state.parse_san("Nf3").unwrap());
+    assert_eq!(state.pawn_zobrist(), pawns);
+    assert_eq!(table.get(&state), structure);
+
+    state.perform(state.parse_san("e5").unwrap());
+    assert_ne!(state.pawn_zobrist(), pawns);
+    assert_eq!(table.get(&state), PawnStructure::new(&state));
+}
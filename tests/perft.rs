@@ -0,0 +1,62 @@
+use schaakmaat::chess::Chess;
+use schaakmaat::fen::STARTING_POSITION;
+
+/// The reference positions and node counts from the Chess Programming Wiki, see
+/// <https://www.chessprogramming.org/Perft_Results>.
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+fn assert_perft(fen: &str, counts: &[u64]) {
+    let state = Chess::from_fen(fen).unwrap();
+    for (depth, &count) in counts.iter().enumerate() {
+        assert_eq!(state.perft(depth as u8), count, "depth {depth} of {fen}");
+    }
+}
+
+#[test]
+fn starting_position() {
+    assert_perft(STARTING_POSITION, &[1, 20, 400, 8_902, 197_281, 4_865_609]);
+}
+
+#[test]
+fn kiwipete() {
+    assert_perft(KIWIPETE, &[1, 48, 2_039, 97_862, 4_085_603]);
+}
+
+#[test]
+fn position_3() {
+    assert_perft(POSITION_3, &[1, 14, 191, 2_812, 43_238]);
+}
+
+#[test]
+fn position_4() {
+    assert_perft(POSITION_4, &[1, 6, 264, 9_467]);
+}
+
+#[test]
+fn position_5() {
+    assert_perft(POSITION_5, &[1, 44, 1_486, 62_379]);
+}
+
+#[test]
+fn position_6() {
+    assert_perft(POSITION_6, &[1, 46, 2_079, 89_890]);
+}
+
+#[test]
+fn divide() {
+    let state = Chess::from_fen(KIWIPETE).unwrap();
+    let divide = state.divide(2);
+    assert_eq!(divide.len(), 48);
+    assert_eq!(divide.iter().map(|(_, count)| count).sum::<u64>(), 2_039);
+
+    let castle = state.parse_san("O-O").unwrap();
+    assert!(divide.contains(&(castle, 43)));
+}
@@ -0,0 +1,223 @@
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+use schaakmaat::chess::{Chess, Color, Outcome};
+use schaakmaat::computer::{self, Score, SearchLimits};
+use schaakmaat::game::Game;
+use schaakmaat::pawns::PawnTable;
+use schaakmaat::transposition::TranspositionTable;
+
+const MATE_IN_TWO: &str = "3r2rk/p4p1p/3p1Pp1/3R4/2p1B2Q/8/1q4PP/4R1K1 w - - 0 1";
+
+#[test]
+fn deepens_until_depth() {
+    let mut game = Game::from(Chess::from_fen(MATE_IN_TWO).unwrap());
+    let mut table = TranspositionTable::new(1);
+    let mut pawns = PawnTable::new(1);
+    let limits = SearchLimits {
+        depth: Some(3),
+        ..SearchLimits::default()
+    };
+
+    for _ in 0..3 {
+        let mut depths = Vec::new();
+        let best = computer::iterative_deepening(
+            &game,
+            limits,
+            &mut table,
+            &mut pawns,
+            &AtomicBool::new(false),
+            |result| depths.push(result.depth),
+        );
+        assert_eq!(depths, [1, 2, 3]);
+        game.perform(best.best_move().unwrap());
+    }
+
+    assert_eq!(game.outcome(), Some(Outcome::Winner(Color::White)));
+}
+
+#[test]
+fn principal_variation() {
+    let state = Chess::from_fen(MATE_IN_TWO).unwrap();
+    let limits = SearchLimits {
+        depth: Some(3),
+        ..SearchLimits::default()
+    };
+
+    let result = computer::iterative_deepening(
+        &Game::from(state),
+        limits,
+        &mut TranspositionTable::new(1),
+        &mut PawnTable::new(1),
+        &AtomicBool::new(false),
+        |_| {},
+    );
+    assert_eq!(result.depth, 3);
+    assert_eq!(result.score.to_string(), "mate 2");
+    assert!(result.nodes > 0);
+
+    // The variation is the mate itself, with black's best defence in between
+    let mut line = state;
+    for m in &result.pv {
+        assert!(line.moves().any(|legal| legal == *m));
+        line.perform(*m);
+    }
+    assert_eq!(result.pv.len(), 3);
+    assert_eq!(line.outcome(), Some(Outcome::Winner(Color::White)));
+}
+
+#[test]
+fn node_limit() {
+    let state = Chess::new();
+    let limits = SearchLimits {
+        nodes: Some(1),
+        ..SearchLimits::default()
+    };
+
+    // Not even a single depth completes, but there is still a move to play
+    let mut reported = false;
+    let best = computer::iterative_deepening(
+        &Game::from(state),
+        limits,
+        &mut TranspositionTable::new(1),
+        &mut PawnTable::new(1),
+        &AtomicBool::new(false),
+        |_| reported = true,
+    );
+    assert!(!reported);
+    assert!(state.moves().any(|m| Some(m) == best.best_move()));
+}
+
+#[test]
+fn move_time() {
+    let state = Chess::new();
+    let limits = SearchLimits {
+        move_time: Some(Duration::from_millis(200)),
+        ..SearchLimits::default()
+    };
+
+    let start = Instant::now();
+    let best = computer::iterative_deepening(
+        &Game::from(state),
+        limits,
+        &mut TranspositionTable::new(1),
+        &mut PawnTable::new(1),
+        &AtomicBool::new(false),
+        |_| {},
+    );
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(best.best_move().is_some());
+}
+
+#[test]
+fn stopped() {
+    let state = Chess::new();
+    let best = computer::iterative_deepening(
+        &Game::from(state),
+        SearchLimits::default(),
+        &mut TranspositionTable::new(1),
+        &mut PawnTable::new(1),
+        &AtomicBool::new(true),
+        |_| {},
+    );
+    assert!(best.best_move().is_some());
+}
+
+#[test]
+fn repetition_is_a_draw() {
+    // Black is a queen up, so white is happy to repeat the position
+    let mut game = Game::from(Chess::from_fen("kq6/8/8/8/8/8/8/6NK w - - 0 1").unwrap());
+    for san in ["Nf3", "Qc8", "Ng1", "Qb8"] {
+        game.perform(game.chess().parse_san(san).unwrap());
+    }
+    assert_eq!(game.repetitions(), 2);
+
+    let limits = SearchLimits {
+        depth: Some(2),
+        ..SearchLimits::default()
+    };
+    let result = computer::iterative_deepening(
+        &game,
+        limits,
+        &mut TranspositionTable::new(1),
+        &mut PawnTable::new(1),
+        &AtomicBool::new(false),
+        |_| {},
+    );
+    assert_eq!(result.best_move(), game.chess().parse_san("Nf3").ok());
+    assert_eq!(result.score, Score::DRAW);
+}
+
+#[test]
+fn clock_time() {
+    let limits = SearchLimits {
+        clock: Some(Duration::from_secs(60)),
+        increment: Duration::from_secs(2),
+        ..SearchLimits::default()
+    };
+    assert_eq!(limits.time(), Some(Duration::from_millis(3500)));
+
+    let move_time = SearchLimits {
+        move_time: Some(Duration::from_secs(5)),
+        ..limits
+    };
+    assert_eq!(move_time.time(), Some(Duration::from_secs(5)));
+
+    assert_eq!(SearchLimits::default().time(), None);
+}
+
+#[test]
+fn quiescence_sees_recapture() {
+    // Taking the pawn on e5 wins a pawn at first, but the queen is then taken by the pawn on d6
+    let state = Chess::from_fen("4k3/8/3p4/4p3/8/8/4Q3/4K3 w - - 0 1").unwrap();
+    let greedy = state.parse_san("Qxe5+").unwrap();
+
+    assert_eq!(computer::alpha_beta(&state, 1).m, Some(greedy));
+    let with_table = computer::alpha_beta_with_table(&state, 1, &mut TranspositionTable::new(1));
+    assert_eq!(with_table.m, Some(greedy));
+    let best = computer::alpha_beta_with_quiescence(&state, 1);
+    assert_ne!(best.m, Some(greedy));
+}
+
+#[test]
+fn quiescence_searches_evasions() {
+    // The knight checks the king and attacks the queen, which is lost once the king steps aside
+    let state = Chess::from_fen("q3k3/8/8/1N6/8/8/8/6K1 w - - 0 1").unwrap();
+    let fork = state.parse_san("Nc7+").unwrap();
+
+    let best = computer::alpha_beta_with_quiescence(&state, 1);
+    assert_eq!(best.m, Some(fork));
+    assert!(best.score > Score::centipawns(0));
+}
+
+#[test]
+fn quiescence_sees_stalemate() {
+    // Taking the knight leaves black without a legal move, while any pawn move keeps the win
+    let state = Chess::from_fen("7k/5K1p/7P/8/8/3B4/PP6/1n6 w - - 0 1").unwrap();
+    let stalemate = state.parse_san("Bxb1").unwrap();
+
+    let best = computer::alpha_beta_with_quiescence(&state, 1);
+    assert_ne!(best.m, Some(stalemate));
+    assert!(best.score > Score::centipawns(0));
+}
+
+#[test]
+fn captures_only() {
+    let state =
+        Chess::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    let mut captures: Vec<String> = state.captures().map(|m| state.move_to_san(m)).collect();
+    captures.sort();
+    assert_eq!(
+        captures,
+        ["Bxa6", "Nxd7", "Nxf7", "Nxg6", "Qxf6", "Qxh3", "dxe6", "gxh3"]
+    );
+
+    let en_passant =
+        Chess::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3").unwrap();
+    let captures: Vec<String> = en_passant
+        .captures()
+        .map(|m| en_passant.move_to_san(m))
+        .collect();
+    assert_eq!(captures, ["exf6"]);
+}
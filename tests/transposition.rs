@@ -0,0 +1,55 @@
+use schaakmaat::chess::Chess;
+use schaakmaat::computer::{self, Score};
+use schaakmaat::transposition::{Bound, Entry, TranspositionTable};
+
+#[test]
+fn sized_by_memory() {
+    let small = TranspositionTable::new(1);
+    let large = TranspositionTable::new(4);
+    assert!(small.capacity() > 1000);
+    assert!(large.capacity() / small.capacity() == 4);
+    assert_eq!(TranspositionTable::new(0).capacity(), 1);
+}
+
+#[test]
+fn hits_misses_and_clear() {
+    let mut table = TranspositionTable::new(1);
+    let entry = Entry {
+        zobrist: Chess::new().zobrist(),
+        depth: 3,
+        score: Score::DRAW,
+        bound: Bound::Exact,
+        best_move: Chess::new().moves().next(),
+    };
+
+    assert_eq!(table.get(entry.zobrist), None);
+    table.insert(entry);
+    assert_eq!(table.get(entry.zobrist), Some(entry));
+    // A different position that happens to share the slot is not mistaken for this one
+    assert_eq!(table.get(entry.zobrist + table.capacity() as u64), None);
+    assert_eq!((table.hits(), table.misses()), (1, 2));
+
+    table.clear();
+    assert_eq!(table.get(entry.zobrist), None);
+    assert_eq!((table.hits(), table.misses()), (0, 1));
+}
+
+#[test]
+fn search_reuses_results() {
+    let state = Chess::new();
+    let mut table = TranspositionTable::new(1);
+
+    // Four moves deep, the knights can reach the same positions in different orders
+    let first = computer::alpha_beta_with_table(&state, 4, &mut table);
+    assert!(table.hits() > 0);
+
+    // The whole search is stored, so searching again finds every position it looks up
+    let hits = table.hits();
+    let misses = table.misses();
+    assert_eq!(
+        computer::alpha_beta_with_table(&state, 4, &mut table),
+        first
+    );
+    assert!(table.hits() > hits);
+    assert_eq!(table.misses(), misses);
+}
@@ -0,0 +1,198 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+struct Engine {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Engine {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_schaakmaat"))
+            .arg("uci")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Engine {
+            child,
+            stdin,
+            stdout,
+        }
+    }
+
+    fn send(&mut self, line: &str) {
+        writeln!(self.stdin, "{line}").unwrap();
+    }
+
+    /// Reads lines until one starts with the given prefix, returning all lines read.
+    fn read_until(&mut self, prefix: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            assert_ne!(
+                self.stdout.read_line(&mut line).unwrap(),
+                0,
+                "engine exited"
+            );
+            let line = line.trim_end().to_owned();
+            let done = line.starts_with(prefix);
+            lines.push(line);
+            if done {
+                return lines;
+            }
+        }
+    }
+
+    fn quit(mut self) {
+        self.send("quit");
+        assert!(self.child.wait().unwrap().success());
+    }
+}
+
+#[test]
+fn handshake() {
+    let mut engine = Engine::start();
+
+    engine.send("uci");
+    let lines = engine.read_until("uciok");
+    assert!(lines.iter().any(|line| line.starts_with("id name ")));
+
+    engine.send("isready");
+    assert_eq!(engine.read_until("readyok"), ["readyok"]);
+
+    engine.quit();
+}
+
+#[test]
+fn go_depth_finds_mate() {
+    let mut engine = Engine::start();
+
+    engine.send("ucinewgame");
+    engine.send("position fen r1b2rk1/pppp2p1/8/3qPN1Q/8/8/P5PP/b1B2R1K w - - 0 1");
+    engine.send("go depth 1");
+    let lines = engine.read_until("bestmove");
+    assert!(lines[0].starts_with("info depth 1 score mate 1 nodes "));
+    assert!(lines[0].ends_with(" pv f5e7"));
+    assert_eq!(lines.last().unwrap(), "bestmove f5e7");
+
+    engine.quit();
+}
+
+#[test]
+fn position_with_moves() {
+    let mut engine = Engine::start();
+
+    // The queen moves next to the white king, which can take it
+    engine.send("position fen 7k/8/8/8/8/8/8/K6q w - - 0 1 moves a1a2 h1b1");
+    engine.send("go depth 1");
+    assert_eq!(
+        engine.read_until("bestmove").pop().unwrap(),
+        "bestmove a2b1"
+    );
+
+    engine.send("position startpos moves e2e4 e2e4");
+    let error = engine.read_until("info string").pop().unwrap();
+    assert_eq!(error, "info string illegal move 'e2e4'");
+
+    engine.quit();
+}
+
+#[test]
+fn perft() {
+    let mut engine = Engine::start();
+
+    engine.send("position startpos moves e2e4");
+    engine.send("go perft 2");
+    let lines = engine.read_until("Nodes searched");
+    assert_eq!(lines.len(), 22);
+    assert!(lines.contains(&"e7e5: 29".to_owned()));
+    assert_eq!(lines.last().unwrap(), "Nodes searched: 600");
+
+    engine.quit();
+}
+
+#[test]
+fn stop_infinite() {
+    let mut engine = Engine::start();
+
+    engine.send("position startpos");
+    engine.send("go infinite");
+    engine.send("stop");
+    let bestmove = engine.read_until("bestmove").pop().unwrap();
+    assert_ne!(bestmove, "bestmove 0000");
+
+    engine.quit();
+}
+
+#[test]
+fn stop_during_depth() {
+    let mut engine = Engine::start();
+
+    // The search is well into a depth that takes far longer than this to complete
+    engine.send("position startpos");
+    engine.send("go infinite");
+    thread::sleep(Duration::from_secs(1));
+    let stopped = Instant::now();
+    engine.send("stop");
+    engine.read_until("bestmove");
+    assert!(stopped.elapsed() < Duration::from_millis(500));
+
+    engine.quit();
+}
+
+#[test]
+fn infinite_waits_for_stop() {
+    let mut engine = Engine::start();
+
+    // Stalemate, so the search is over at once, but it may not say so before it is stopped
+    engine.send("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+    engine.send("go infinite");
+    thread::sleep(Duration::from_millis(100));
+    engine.send("isready");
+    let lines = engine.read_until("readyok");
+    assert!(!lines.iter().any(|line| line.starts_with("bestmove")));
+
+    engine.send("stop");
+    assert_eq!(
+        engine.read_until("bestmove").pop().unwrap(),
+        "bestmove 0000"
+    );
+
+    engine.quit();
+}
+
+#[test]
+fn unusable_limits() {
+    let mut engine = Engine::start();
+
+    // After running out of time, the clock is negative, but a move must still be made at once
+    engine.send("position startpos moves e2e4");
+    for go in ["go wtime 1000 btime -50", "go movetime x", "go depth -1"] {
+        let start = Instant::now();
+        engine.send(go);
+        let bestmove = engine.read_until("bestmove").pop().unwrap();
+        assert_ne!(bestmove, "bestmove 0000");
+        assert!(start.elapsed() < Duration::from_millis(500), "{go}");
+    }
+
+    engine.quit();
+}
+
+#[test]
+fn hash_clamped() {
+    let mut engine = Engine::start();
+
+    // An empty table would have no slot to put positions in
+    engine.send("setoption name Hash value 0");
+    engine.send("position startpos");
+    engine.send("go depth 2");
+    engine.read_until("bestmove");
+
+    engine.quit();
+}
@@ -0,0 +1,67 @@
+use schaakmaat::chess::Chess;
+use schaakmaat::fen::STARTING_POSITION;
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+fn play(fen: &str, moves: &[&str]) -> Chess {
+    let mut state = Chess::from_fen(fen).unwrap();
+    for san in moves {
+        state.perform(state.parse_san(san).unwrap());
+    }
+    state
+}
+
+#[test]
+fn incremental_matches_from_scratch() {
+    for fen in [STARTING_POSITION, KIWIPETE] {
+        let mut state = Chess::from_fen(fen).unwrap();
+        // Always playing the last move reaches castles, captures and promotions quickly
+        for _ in 0..40 {
+            let Some(m) = state.moves().last() else {
+                break;
+            };
+            state.perform(m);
+            let fresh = Chess::from_fen(&state.to_fen()).unwrap();
+            assert_eq!(state.zobrist(), fresh.zobrist(), "{}", state.to_fen());
+            assert_eq!(
+                state.pawn_zobrist(),
+                fresh.pawn_zobrist(),
+                "{}",
+                state.to_fen()
+            );
+        }
+    }
+}
+
+#[test]
+fn transpositions() {
+    let a = play(STARTING_POSITION, &["Nf3", "Nf6", "Nc3", "Nc6"]);
+    let b = play(STARTING_POSITION, &["Nc3", "Nc6", "Nf3", "Nf6"]);
+    assert_eq!(a.zobrist(), b.zobrist());
+
+    let back = play(STARTING_POSITION, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+    assert_eq!(back.zobrist(), Chess::new().zobrist());
+}
+
+#[test]
+fn turn_castling_and_en_passant() {
+    let white = Chess::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+    let black = Chess::from_fen("4k3/8/8/8/8/8/8/4K2R b K - 0 1").unwrap();
+    let no_castling = Chess::from_fen("4k3/8/8/8/8/8/8/4K2R w - - 0 1").unwrap();
+    assert_ne!(white.zobrist(), black.zobrist());
+    assert_ne!(white.zobrist(), no_castling.zobrist());
+
+    // Black can capture the leaping pawn, so the en passant tile matters
+    let capturable = Chess::from_fen("4k3/8/8/8/2Pp4/8/8/4K3 b - c3 0 1").unwrap();
+    let quiet = Chess::from_fen("4k3/8/8/8/2Pp4/8/8/4K3 b - - 0 1").unwrap();
+    assert_ne!(capturable.zobrist(), quiet.zobrist());
+
+    // Nobody can capture the leaping pawn, so the position is the same as without the leap
+    let uncapturable = Chess::from_fen("4k3/8/8/8/2P4p/8/8/4K3 b - c3 0 1").unwrap();
+    let without = Chess::from_fen("4k3/8/8/8/2P4p/8/8/4K3 b - - 0 1").unwrap();
+    assert_eq!(uncapturable.zobrist(), without.zobrist());
+
+    let leap = play(STARTING_POSITION, &["e4"]);
+    let without = Chess::from_fen(&leap.to_fen().replace(" e3 ", " - ")).unwrap();
+    assert_eq!(leap.zobrist(), without.zobrist());
+}